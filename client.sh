@@ -54,8 +54,9 @@ transfer-ownership() {
 }
 
 
-query-select() {
-  query='{"select":{"fields":null}}'
+query-chat() {
+  fields=${1:-'["title"]'}
+  query='{"chat":{"limit":10,"fields":'$fields'}}'
   flags="--chain-id $CHAIN_ID --output json --node $NODE"
   echo junod query wasm contract-state smart $CONTRACT_ADDR "$query" $flags
   response=$(junod query wasm contract-state smart $CONTRACT_ADDR "$query" $flags)
@@ -69,8 +70,8 @@ case $CMD in
   transfer-ownership)
     transfer-ownership $1
     ;;
-  query-select) 
-    query-select
+  query-chat)
+    query-chat "$1"
     ;;
   *)
     echo "unrecognized option: $CMD" >&2
@@ -10,10 +10,8 @@ use cw_post::{
     models::{Config, FeeParams},
     msg::{InstantiateMsg, MigrateMsg, NodeInitArgs},
     tokens::Token,
-    CwPostContract, CwPostExecuteMsgFns, CwPostQueryMsgFns,
+    CwPostContract,
 };
-use dotenv;
-use pretty_env_logger;
 
 const FEE_COLLECTION_ADDR: &str = "juno1rec44j9xq8aj4w5kun796f89njzvdlezwk7cy4";
 
@@ -42,16 +40,37 @@ pub fn main() -> anyhow::Result<()> {
                         text: Uint128::zero(),
                         tag: Uint128::zero(),
                         tip_pct: Uint128::zero(),
+                        body_ref: None,
                     },
+                    max_depth: None,
+                    limits: None,
+                    links: None,
+                    sanitize_mode: None,
+                    node_ttl: None,
+                    prune_bounty: None,
+                    max_pins: None,
+                    bounty_fallback: None,
+                    claimable_tips: None,
                 },
                 operator: Some(sender.clone()),
                 root: NodeInitArgs {
                     parent_id: "".to_string(),
                     title: "Test thread".to_string(),
                     body: None,
+                    body_ref: None,
                     links: None,
                     tags: None,
-                    nsfw: None,
+                    labels: None,
+                    lang: None,
+                    translations: None,
+                    reply_policy: None,
+                    child_reply_policy: None,
+                    kind: None,
+                    quote: None,
+                    mentions: None,
+                    poll: None,
+                    publish_at: None,
+                    expires_at: None,
                 },
             },
             Some(&sender),
@@ -69,7 +88,7 @@ pub fn main() -> anyhow::Result<()> {
             );
         }
     } else {
-        cw_post_contract.migrate_if_needed(&MigrateMsg {})?;
+        cw_post_contract.migrate_if_needed(&MigrateMsg { batch_size: None })?;
     }
 
     Ok(())
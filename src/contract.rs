@@ -1,14 +1,31 @@
 use crate::error::ContractError;
+use crate::execute::accept::exec_accept_answer;
+use crate::execute::bounty::{exec_award_bounty, exec_fund_bounty, exec_settle_bounty};
+use crate::execute::claim::exec_claim;
 use crate::execute::delete::exec_delete_node;
 use crate::execute::like::exec_toggle_like;
+use crate::execute::pin::exec_toggle_pin;
+use crate::execute::prune::exec_prune;
 use crate::execute::react::exec_toggle_reaction;
 use crate::execute::reply::exec_reply;
+use crate::execute::reply_policy::exec_set_reply_policy;
 use crate::execute::tip::exec_tip;
+use crate::execute::vote::exec_vote;
 use crate::execute::{configure::exec_configure, Context};
+use crate::migrations::{
+    migrate_liked_ids, migrate_node_labels, migrate_node_storage, MigrationProgress, DEFAULT_MIGRATION_BATCH_SIZE,
+};
 use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, NodesQueryMsg, QueryMsg};
+use crate::query::body::query_verify_body;
+use crate::query::bounty::query_bounty;
+use crate::query::claimable::query_claimable;
 use crate::query::cost::query_cost;
 use crate::query::info::query_info;
-use crate::query::nodes::{query_chat, query_nodes_by_ids, query_nodes_by_parent_id, query_nodes_by_tag};
+use crate::query::nodes::{
+    query_chat, query_mentions, query_nodes_by_ids, query_nodes_by_parent_id, query_nodes_by_tag, query_pinned,
+};
+use crate::query::poll::query_poll_results;
+use crate::query::stats::{query_top_earners, query_top_tippers, query_user_stats};
 use crate::query::ReadonlyContext;
 use crate::state;
 use cosmwasm_std::{entry_point, to_json_binary};
@@ -26,7 +43,7 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(state::init(Context { deps, env, info }, msg)?)
+    state::init(Context { deps, env, info }, msg)
 }
 
 #[entry_point]
@@ -50,6 +67,24 @@ pub fn execute(
         ExecuteMsg::Tip(msg) => exec_tip(ctx, msg),
         // Delete a node's content
         ExecuteMsg::Delete(msg) => exec_delete_node(ctx, msg),
+        // Vote in a node's poll
+        ExecuteMsg::Vote(msg) => exec_vote(ctx, msg),
+        // Delete expired nodes, paying the sender a bounty
+        ExecuteMsg::Prune(msg) => exec_prune(ctx, msg),
+        // Toggle a pin on a given node under its parent
+        ExecuteMsg::Pin(msg) => exec_toggle_pin(ctx, msg),
+        // Change who may reply to a node the sender authored
+        ExecuteMsg::SetReplyPolicy(msg) => exec_set_reply_policy(ctx, msg),
+        // Accept a reply as the answer to a question the sender authored
+        ExecuteMsg::AcceptAnswer(msg) => exec_accept_answer(ctx, msg),
+        // Add funds to a node's bounty, held in escrow
+        ExecuteMsg::FundBounty(msg) => exec_fund_bounty(ctx, msg),
+        // Award a node's bounty to replies the sender didn't author
+        ExecuteMsg::AwardBounty(msg) => exec_award_bounty(ctx, msg),
+        // Distribute a bounty's unawarded amount after its deadline
+        ExecuteMsg::SettleBounty(msg) => exec_settle_bounty(ctx, msg),
+        // Withdraw the sender's claimable balance of a token
+        ExecuteMsg::Claim(msg) => exec_claim(ctx, msg),
     }
 }
 
@@ -67,6 +102,24 @@ pub fn query(
         QueryMsg::Cost(args) => to_json_binary(&query_cost(ctx, args)?),
         // Paginate over the nodes in a flat list in order or creation
         QueryMsg::Chat(params) => to_json_binary(&query_chat(ctx, params)?),
+        // Paginate over the nodes mentioning a given address
+        QueryMsg::Mentions(params) => to_json_binary(&query_mentions(ctx, params)?),
+        // Get the current vote tallies of a node's poll
+        QueryMsg::PollResults(params) => to_json_binary(&query_poll_results(ctx, params)?),
+        // Check a blob against a node's off-chain body reference
+        QueryMsg::VerifyBody(params) => to_json_binary(&query_verify_body(ctx, params)?),
+        // Get the pinned children of a given node
+        QueryMsg::Pinned(params) => to_json_binary(&query_pinned(ctx, params)?),
+        // Get a node's open bounty & its funders
+        QueryMsg::Bounty(params) => to_json_binary(&query_bounty(ctx, params)?),
+        // Get an address's claimable token balances
+        QueryMsg::Claimable(params) => to_json_binary(&query_claimable(ctx, params)?),
+        // Get an address's tip totals received & given
+        QueryMsg::UserStats(params) => to_json_binary(&query_user_stats(ctx, params)?),
+        // Paginate over addresses in order of tips received
+        QueryMsg::TopEarners(params) => to_json_binary(&query_top_earners(ctx, params)?),
+        // Paginate over addresses in order of tips given
+        QueryMsg::TopTippers(params) => to_json_binary(&query_top_tippers(ctx, params)?),
         // Query one or more nodes....
         QueryMsg::Nodes(msg) => match msg {
             // Query nodes by parent ID
@@ -84,8 +137,35 @@ pub fn query(
 pub fn migrate(
     deps: DepsMut,
     _env: Env,
-    _msg: MigrateMsg,
+    msg: MigrateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    Ok(Response::default())
+    let batch_size = msg.batch_size.unwrap_or(DEFAULT_MIGRATION_BATCH_SIZE);
+
+    // Consolidate per-node data from legacy storage maps
+    let storage_progress = migrate_node_storage(deps.storage, batch_size)?;
+
+    // Replace the nsfw flag with content labels, once nodes are consolidated
+    let labels_progress = if storage_progress.is_complete {
+        migrate_node_labels(deps.storage, batch_size)?
+    } else {
+        MigrationProgress {
+            n_migrated: 0,
+            is_complete: false,
+        }
+    };
+
+    // Index existing likes by address. This only reads index keys, so it
+    // needn't wait for nodes to be consolidated.
+    let likes_progress = migrate_liked_ids(deps.storage, batch_size)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("n_nodes_migrated", storage_progress.n_migrated.to_string())
+        .add_attribute("n_nodes_labeled", labels_progress.n_migrated.to_string())
+        .add_attribute("n_liked_nodes_indexed", likes_progress.n_migrated.to_string())
+        .add_attribute(
+            "is_complete",
+            (labels_progress.is_complete && likes_progress.is_complete).to_string(),
+        ))
 }
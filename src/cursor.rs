@@ -0,0 +1,181 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, to_json_binary, Addr, Binary, StdResult, Uint128};
+
+use crate::error::ContractError;
+
+/// Version of the encoding used for new cursors
+pub const CURSOR_VERSION: u8 = 1;
+
+/// The kind of paginated query that issued a cursor
+#[cw_serde]
+pub enum CursorKind {
+    ByIds,
+    ByParentTime,
+    ByParentLikes,
+    ByTag,
+    Chat,
+    Mentions,
+    TopEarners,
+    TopTippers,
+}
+
+/// Position in a query's underlying index, after which the next page begins
+#[cw_serde]
+pub enum CursorKey {
+    Id(String),
+    Ranked { rank: u32, id: String },
+    Amount { amount: Uint128, address: Addr },
+}
+
+#[cw_serde]
+struct CursorData {
+    version: u8,
+    kind: CursorKind,
+    key: CursorKey,
+}
+
+/// Opaque pagination cursor, returned by one page of a query and passed back
+/// unchanged to fetch the next
+#[cw_serde]
+pub struct Cursor(Binary);
+
+impl Cursor {
+    pub fn new(
+        kind: CursorKind,
+        key: CursorKey,
+    ) -> StdResult<Self> {
+        Ok(Self(to_json_binary(&CursorData {
+            version: CURSOR_VERSION,
+            kind,
+            key,
+        })?))
+    }
+
+    /// Decode the cursor, ensuring it was issued by the given kind of query
+    pub fn decode(
+        &self,
+        kind: CursorKind,
+    ) -> Result<CursorKey, ContractError> {
+        let data: CursorData = from_json(&self.0).map_err(|_| ContractError::InvalidCursor {
+            reason: "malformed cursor".to_owned(),
+        })?;
+        if data.version != CURSOR_VERSION {
+            return Err(ContractError::InvalidCursor {
+                reason: format!("unsupported cursor version {}", data.version),
+            });
+        }
+        if data.kind != kind {
+            return Err(ContractError::InvalidCursor {
+                reason: format!("expected {:?} cursor, got {:?}", kind, data.kind),
+            });
+        }
+        Ok(data.key)
+    }
+
+    /// Decode a cursor whose key is a node ID
+    pub fn decode_id(
+        &self,
+        kind: CursorKind,
+    ) -> Result<String, ContractError> {
+        match self.decode(kind)? {
+            CursorKey::Id(id) => Ok(id),
+            _ => Err(ContractError::InvalidCursor {
+                reason: "expected node ID cursor key".to_owned(),
+            }),
+        }
+    }
+
+    /// Decode a cursor whose key is a node ID with an associated rank
+    pub fn decode_ranked(
+        &self,
+        kind: CursorKind,
+    ) -> Result<(u32, String), ContractError> {
+        match self.decode(kind)? {
+            CursorKey::Ranked { rank, id } => Ok((rank, id)),
+            _ => Err(ContractError::InvalidCursor {
+                reason: "expected ranked cursor key".to_owned(),
+            }),
+        }
+    }
+
+    /// Decode a cursor whose key is an address with an associated amount
+    pub fn decode_amount(
+        &self,
+        kind: CursorKind,
+    ) -> Result<(Uint128, Addr), ContractError> {
+        match self.decode(kind)? {
+            CursorKey::Amount { amount, address } => Ok((amount, address)),
+            _ => Err(ContractError::InvalidCursor {
+                reason: "expected amount cursor key".to_owned(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_invalid<T: std::fmt::Debug>(result: Result<T, ContractError>) {
+        assert!(
+            matches!(result, Err(ContractError::InvalidCursor { .. })),
+            "expected InvalidCursor, got {:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn decodes_cursor_of_same_kind() {
+        let cursor = Cursor::new(
+            CursorKind::ByTag,
+            CursorKey::Ranked {
+                rank: 3,
+                id: "7".to_owned(),
+            },
+        )
+        .unwrap();
+        assert_eq!(cursor.decode_ranked(CursorKind::ByTag).unwrap(), (3, "7".to_owned()));
+    }
+
+    #[test]
+    fn rejects_malformed_cursor() {
+        let cursor = Cursor(Binary::from(b"not a cursor".as_slice()));
+        assert_invalid(cursor.decode(CursorKind::Chat));
+    }
+
+    #[test]
+    fn rejects_version_mismatch() {
+        let cursor = Cursor(
+            to_json_binary(&CursorData {
+                version: CURSOR_VERSION + 1,
+                kind: CursorKind::Chat,
+                key: CursorKey::Id("1".to_owned()),
+            })
+            .unwrap(),
+        );
+        assert_invalid(cursor.decode(CursorKind::Chat));
+    }
+
+    #[test]
+    fn rejects_kind_mismatch() {
+        let cursor = Cursor::new(CursorKind::ByParentTime, CursorKey::Id("1".to_owned())).unwrap();
+        assert_invalid(cursor.decode_id(CursorKind::Chat));
+    }
+
+    #[test]
+    fn rejects_key_shape_mismatch() {
+        let cursor = Cursor::new(CursorKind::ByParentLikes, CursorKey::Id("1".to_owned())).unwrap();
+        assert_invalid(cursor.decode_ranked(CursorKind::ByParentLikes));
+
+        let cursor = Cursor::new(
+            CursorKind::Chat,
+            CursorKey::Ranked {
+                rank: 1,
+                id: "1".to_owned(),
+            },
+        )
+        .unwrap();
+        assert_invalid(cursor.decode_id(CursorKind::Chat));
+        assert_invalid(cursor.decode_amount(CursorKind::Chat));
+    }
+}
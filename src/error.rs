@@ -11,6 +11,9 @@ pub enum ContractError {
 
   #[error("ValidationError: {reason:?}")]
   ValidationError { reason: String },
+
+  #[error("InvalidCursor: {reason:?}")]
+  InvalidCursor { reason: String },
 }
 
 impl From<ContractError> for StdError {
@@ -0,0 +1,61 @@
+use crate::{
+    error::ContractError,
+    models::NodeKind,
+    msg::AcceptAnswerMsg,
+    state::{ACCEPTED_ANSWERS, NODE_ATTRS, NODE_HEADER},
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+/// Set, change or clear the accepted answer to a question. Only the
+/// question's author may do so.
+pub fn exec_accept_answer(
+    ctx: Context,
+    msg: AcceptAnswerMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, info, .. } = ctx;
+    let AcceptAnswerMsg { question_id, answer_id } = msg;
+
+    let question = NODE_HEADER
+        .may_load(deps.storage, &question_id)?
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} not found", question_id),
+        })?;
+
+    if NODE_ATTRS.load(deps.storage, &question_id)?.kind != Some(NodeKind::Question) {
+        return Err(ContractError::ValidationError {
+            reason: format!("node {} is not a question", question_id),
+        });
+    }
+
+    if info.sender != question.created_by {
+        return Err(ContractError::NotAuthorized {
+            reason: "only the question's author can accept an answer".to_owned(),
+        });
+    }
+
+    let action = if let Some(answer_id) = &answer_id {
+        // Only direct replies to the question can be accepted
+        let is_reply = NODE_HEADER
+            .may_load(deps.storage, answer_id)?
+            .map(|answer| answer.parent_id == question_id)
+            .unwrap_or_default();
+        if !is_reply {
+            return Err(ContractError::ValidationError {
+                reason: format!("node {} is not a reply to question {}", answer_id, question_id),
+            });
+        }
+        ACCEPTED_ANSWERS.save(deps.storage, &question_id, answer_id)?;
+        "accept_answer"
+    } else {
+        ACCEPTED_ANSWERS.remove(deps.storage, &question_id);
+        "clear_answer"
+    };
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", action),
+        attr("question_id", question_id),
+        attr("answer_id", answer_id.unwrap_or_default()),
+    ]))
+}
@@ -0,0 +1,330 @@
+use crate::{
+    error::ContractError,
+    math::{add_u128, add_u32, mul_ratio_u128, sub_u128},
+    models::{BountyFallback, Config},
+    msg::{AwardBountyMsg, BountyAward, FundBountyMsg, SettleBountyMsg},
+    state::{
+        ensure_not_expired, ensure_published, Bounty, BOUNTIES, BOUNTY_FUNDS, CONFIG, IX_PARENT_2_RANKED_CHILD_ID,
+        MAX_BOUNTY_FUNDERS, MAX_BOUNTY_SPLIT, NODE_HEADER,
+    },
+};
+use cosmwasm_std::{attr, Addr, Order, Response, StdResult, Uint128};
+
+use super::{
+    tip::{apply_tip_if_exists, pay_out, take_funds},
+    Context,
+};
+
+/// Add funds to a node's bounty, opening it if none is open. Native funds
+/// must be sent with the message, while cw20 funds are transferred from the
+/// sender under an allowance.
+pub fn exec_fund_bounty(
+    ctx: Context,
+    msg: FundBountyMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, info } = ctx;
+    let FundBountyMsg {
+        node_id,
+        amount,
+        deadline,
+    } = msg;
+    let config = CONFIG.load(deps.storage)?;
+
+    let header = NODE_HEADER
+        .may_load(deps.storage, &node_id)?
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} not found", node_id),
+        })?;
+    ensure_published(&header, env.block.time)?;
+    ensure_not_expired(&header, env.block.time)?;
+
+    if amount.is_zero() {
+        return Err(ContractError::ValidationError {
+            reason: "bounty amount must be nonzero".to_owned(),
+        });
+    }
+
+    let mut bounty = match (BOUNTIES.may_load(deps.storage, &node_id)?, deadline) {
+        (Some(_), Some(_)) => {
+            return Err(ContractError::ValidationError {
+                reason: format!("node {} already has an open bounty with a deadline", node_id),
+            });
+        },
+        (Some(bounty), None) => bounty,
+        (None, Some(deadline)) => Bounty {
+            amount: Uint128::zero(),
+            total_funded: Uint128::zero(),
+            deadline,
+            n_funders: 0,
+            token: config.token.to_owned(),
+        },
+        (None, None) => {
+            return Err(ContractError::ValidationError {
+                reason: "a deadline is required to open a bounty".to_owned(),
+            });
+        },
+    };
+
+    if bounty.deadline <= env.block.time {
+        return Err(ContractError::ValidationError {
+            reason: format!("bounty deadline for node {} has passed", node_id),
+        });
+    }
+
+    let prev_amount = BOUNTY_FUNDS.may_load(deps.storage, (&node_id, &info.sender))?;
+    if prev_amount.is_none() {
+        if bounty.n_funders >= MAX_BOUNTY_FUNDERS {
+            return Err(ContractError::ValidationError {
+                reason: format!("max of {} bounty funders reached", MAX_BOUNTY_FUNDERS),
+            });
+        }
+        bounty.n_funders = add_u32(bounty.n_funders, 1)?;
+    }
+
+    bounty.amount = add_u128(bounty.amount, amount)?;
+    bounty.total_funded = add_u128(bounty.total_funded, amount)?;
+
+    BOUNTIES.save(deps.storage, &node_id, &bounty)?;
+    BOUNTY_FUNDS.save(
+        deps.storage,
+        (&node_id, &info.sender),
+        &add_u128(prev_amount.unwrap_or_default(), amount)?,
+    )?;
+
+    let resp = Response::new().add_attributes(vec![
+        attr("action", "fund_bounty"),
+        attr("node_id", node_id),
+        attr("amount", amount.to_string()),
+    ]);
+
+    // Take the funds into escrow
+    let config = in_bounty_token(config, &bounty);
+    take_funds(resp, &config, &info, &env.contract.address, amount)
+}
+
+/// Award part or all of a node's bounty to one or more direct replies by other
+/// accounts. Only the node's author may do so, before the deadline.
+pub fn exec_award_bounty(
+    ctx: Context,
+    msg: AwardBountyMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, info } = ctx;
+    let AwardBountyMsg { node_id, awards } = msg;
+    let config = CONFIG.load(deps.storage)?;
+
+    let header = NODE_HEADER
+        .may_load(deps.storage, &node_id)?
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} not found", node_id),
+        })?;
+    ensure_published(&header, env.block.time)?;
+
+    if info.sender != header.created_by {
+        return Err(ContractError::NotAuthorized {
+            reason: "only the node's author can award its bounty".to_owned(),
+        });
+    }
+
+    let mut bounty = BOUNTIES
+        .may_load(deps.storage, &node_id)?
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} has no open bounty", node_id),
+        })?;
+    let config = in_bounty_token(config, &bounty);
+
+    if bounty.deadline <= env.block.time {
+        return Err(ContractError::ValidationError {
+            reason: format!("bounty deadline for node {} has passed", node_id),
+        });
+    }
+
+    let mut resp = Response::new().add_attributes(vec![attr("action", "award_bounty"), attr("node_id", &node_id)]);
+
+    for BountyAward {
+        node_id: reply_id,
+        amount,
+    } in awards.iter()
+    {
+        let reply = NODE_HEADER
+            .may_load(deps.storage, reply_id)?
+            .filter(|reply| reply.parent_id == node_id)
+            .ok_or_else(|| ContractError::ValidationError {
+                reason: format!("node {} is not a reply to node {}", reply_id, node_id),
+            })?;
+        ensure_published(&reply, env.block.time)?;
+        if reply.created_by == header.created_by {
+            return Err(ContractError::ValidationError {
+                reason: "bounties can't be awarded to the author's own replies".to_owned(),
+            });
+        }
+        if amount.is_zero() || *amount > bounty.amount {
+            return Err(ContractError::ValidationError {
+                reason: format!("award amount must be nonzero & at most {}", bounty.amount),
+            });
+        }
+        bounty.amount = sub_u128(bounty.amount, *amount)?;
+        resp = apply_tip_if_exists(deps.storage, resp, &config, None, reply.created_by, *amount, reply_id)?;
+    }
+
+    BOUNTIES.save(deps.storage, &node_id, &bounty)?;
+
+    Ok(resp)
+}
+
+/// Close a bounty whose deadline has passed, distributing any unawarded
+/// amount according to the configured fallback. Anyone may do so.
+pub fn exec_settle_bounty(
+    ctx: Context,
+    msg: SettleBountyMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, .. } = ctx;
+    let SettleBountyMsg { node_id } = msg;
+    let config = CONFIG.load(deps.storage)?;
+
+    let bounty = BOUNTIES
+        .may_load(deps.storage, &node_id)?
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} has no open bounty", node_id),
+        })?;
+    let config = in_bounty_token(config, &bounty);
+
+    if bounty.deadline > env.block.time {
+        return Err(ContractError::ValidationError {
+            reason: format!("bounty for node {} is open until {}", node_id, bounty.deadline),
+        });
+    }
+
+    let funders = BOUNTY_FUNDS
+        .prefix(&node_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Addr, Uint128)>>>()?;
+
+    BOUNTIES.remove(deps.storage, &node_id);
+    for (addr, _) in funders.iter() {
+        BOUNTY_FUNDS.remove(deps.storage, (&node_id, addr));
+    }
+
+    let mut resp = Response::new().add_attributes(vec![
+        attr("action", "settle_bounty"),
+        attr("node_id", &node_id),
+        attr("amount", bounty.amount.to_string()),
+    ]);
+
+    if bounty.amount.is_zero() {
+        return Ok(resp);
+    }
+
+    // Get the most liked direct replies, by accounts other than the node's
+    // author, if splitting by likes
+    let liked_replies = match config.bounty_fallback.to_owned().unwrap_or(BountyFallback::Refund) {
+        BountyFallback::Refund => vec![],
+        BountyFallback::SplitByLikes => {
+            let author = NODE_HEADER.may_load(deps.storage, &node_id)?.map(|h| h.created_by);
+            let mut replies: Vec<(String, Addr, Uint128)> = Vec::with_capacity(MAX_BOUNTY_SPLIT);
+            for result in
+                IX_PARENT_2_RANKED_CHILD_ID
+                    .sub_prefix(&node_id)
+                    .keys(deps.storage, None, None, Order::Descending)
+            {
+                let (n_likes, reply_id) = result?;
+                if n_likes == 0 || replies.len() == MAX_BOUNTY_SPLIT {
+                    break;
+                }
+                if let Some(reply) = NODE_HEADER.may_load(deps.storage, &reply_id)? {
+                    if Some(&reply.created_by) != author.as_ref() {
+                        replies.push((reply_id, reply.created_by, Uint128::from(n_likes)));
+                    }
+                }
+            }
+            replies
+        },
+    };
+
+    if liked_replies.is_empty() {
+        // Refund funders pro rata
+        resp = resp.add_attribute("fallback", "refund");
+        let weights: Vec<Uint128> = funders.iter().map(|(_, amount)| *amount).collect();
+        for ((addr, _), amount) in funders.iter().zip(split_pro_rata(bounty.amount, &weights)?) {
+            resp = pay_out(deps.storage, resp, &config, addr, amount)?;
+        }
+    } else {
+        // Split among the most liked replies, in proportion to likes
+        resp = resp.add_attribute("fallback", "split_by_likes");
+        let weights: Vec<Uint128> = liked_replies.iter().map(|(_, _, n_likes)| *n_likes).collect();
+        for ((reply_id, author, _), amount) in liked_replies.iter().zip(split_pro_rata(bounty.amount, &weights)?) {
+            if !amount.is_zero() {
+                resp = apply_tip_if_exists(deps.storage, resp, &config, None, author.to_owned(), amount, reply_id)?;
+            }
+        }
+    }
+
+    Ok(resp)
+}
+
+/// Replace the config token with the bounty's, so that the bounty is funded &
+/// paid out in the token it was opened in
+fn in_bounty_token(
+    config: Config,
+    bounty: &Bounty,
+) -> Config {
+    Config {
+        token: bounty.token.to_owned(),
+        ..config
+    }
+}
+
+/// Split an amount in proportion to the given weights, giving any rounding
+/// remainder to the first share
+fn split_pro_rata(
+    amount: Uint128,
+    weights: &[Uint128],
+) -> Result<Vec<Uint128>, ContractError> {
+    let total_weight = weights.iter().try_fold(Uint128::zero(), |n, w| add_u128(n, *w))?;
+    if total_weight.is_zero() {
+        return Ok(vec![Uint128::zero(); weights.len()]);
+    }
+    let mut shares = weights
+        .iter()
+        .map(|w| mul_ratio_u128(amount, *w, total_weight))
+        .collect::<Result<Vec<Uint128>, ContractError>>()?;
+    let remainder = shares.iter().try_fold(amount, |n, share| sub_u128(n, *share))?;
+    if let Some(first) = shares.first_mut() {
+        *first = add_u128(*first, remainder)?;
+    }
+    Ok(shares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(
+        amount: u128,
+        weights: &[u128],
+    ) -> Vec<u128> {
+        let weights: Vec<Uint128> = weights.iter().map(|w| Uint128::from(*w)).collect();
+        split_pro_rata(Uint128::from(amount), &weights)
+            .unwrap()
+            .into_iter()
+            .map(|share| share.u128())
+            .collect()
+    }
+
+    #[test]
+    fn splits_in_proportion_to_weights() {
+        assert_eq!(split(100, &[3, 1]), vec![75, 25]);
+        assert_eq!(split(100, &[1]), vec![100]);
+    }
+
+    #[test]
+    fn gives_rounding_remainder_to_first_share() {
+        assert_eq!(split(100, &[1, 1, 1]), vec![34, 33, 33]);
+        assert_eq!(split(10, &[1, 2, 4]), vec![3, 2, 5]);
+    }
+
+    #[test]
+    fn splits_nothing_without_weight() {
+        assert_eq!(split(100, &[0, 0]), vec![0, 0]);
+        assert_eq!(split(100, &[]), Vec::<u128>::new());
+    }
+}
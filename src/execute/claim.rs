@@ -0,0 +1,42 @@
+use crate::{error::ContractError, math::sub_u128, msg::ClaimMsg, state::CLAIMABLE};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+/// Withdraw some or all of the sender's claimable balance of a token
+pub fn exec_claim(
+    ctx: Context,
+    msg: ClaimMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, info, .. } = ctx;
+    let ClaimMsg { token, amount } = msg;
+    let token_key = token.to_key();
+
+    let mut balance = CLAIMABLE
+        .may_load(deps.storage, (&info.sender, &token_key))?
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("no claimable balance of {}", token_key),
+        })?;
+
+    let amount = amount.unwrap_or(balance.amount);
+    if amount.is_zero() || amount > balance.amount {
+        return Err(ContractError::ValidationError {
+            reason: format!("claim amount must be nonzero & at most {}", balance.amount),
+        });
+    }
+
+    balance.amount = sub_u128(balance.amount, amount)?;
+    if balance.amount.is_zero() {
+        CLAIMABLE.remove(deps.storage, (&info.sender, &token_key));
+    } else {
+        CLAIMABLE.save(deps.storage, (&info.sender, &token_key), &balance)?;
+    }
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "claim"),
+            attr("token", token_key),
+            attr("amount", amount.to_string()),
+        ])
+        .add_submessage(balance.token.transfer(&info.sender, amount)?))
+}
@@ -1,4 +1,8 @@
-use crate::{error::ContractError, models::Config, state::CONFIG};
+use crate::{
+    error::ContractError,
+    models::Config,
+    state::{CONFIG, OPERATOR},
+};
 use cosmwasm_std::{attr, Response};
 
 use super::Context;
@@ -7,7 +11,14 @@ pub fn exec_configure(
     ctx: Context,
     config: Config,
 ) -> Result<Response, ContractError> {
-    let Context { deps, .. } = ctx;
+    let Context { deps, info, .. } = ctx;
+
+    if info.sender != OPERATOR.load(deps.storage)? {
+        return Err(ContractError::NotAuthorized {
+            reason: "only the operator can configure".to_owned(),
+        });
+    }
+
     CONFIG.save(deps.storage, &config)?;
     Ok(Response::new().add_attributes(vec![attr("action", "set_config")]))
 }
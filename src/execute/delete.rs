@@ -4,12 +4,9 @@ use cosmwasm_std::{attr, Response};
 use super::Context;
 
 pub fn exec_delete_node(
-    ctx: Context,
-    msg: DeleteMsg,
+    _ctx: Context,
+    _msg: DeleteMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, .. } = ctx;
-    let node_id = msg.node_id;
-
     // TODO
 
     Ok(Response::new().add_attributes(vec![attr("action", "delete")]))
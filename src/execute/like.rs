@@ -2,26 +2,32 @@ use crate::{
     error::ContractError,
     msg::LikeMsg,
     state::{
-        NodeHeader, CONFIG, IX_ADDR_2_LIKED_ID, IX_LIKED_ID_2_ADDR, IX_PARENT_2_RANKED_CHILD_ID, IX_TAG_2_NODE_ID,
-        NODE_HEADER, NODE_NUM_LIKES, NODE_TAGS,
+        ensure_not_expired, ensure_published, NodeHeader, CONFIG, IX_ADDR_2_LIKED_ID, IX_LIKED_ID_2_ADDR,
+        IX_PARENT_2_RANKED_CHILD_ID, IX_TAG_2_NODE_ID, NODE_ATTRS, NODE_HEADER, NODE_NUM_LIKES,
     },
 };
 use cosmwasm_std::Response;
 
-use super::{tip::apply_tip_if_exists, Context};
+use super::{
+    tip::{apply_tip_if_exists, take_funds},
+    Context,
+};
 
 pub fn exec_toggle_like(
     ctx: Context,
     msg: LikeMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, info, .. } = ctx;
+    let Context { deps, env, info } = ctx;
     let LikeMsg { node_id, tip_amount } = msg;
     let config = CONFIG.load(deps.storage)?;
+    let header = NODE_HEADER.load(deps.storage, &node_id)?;
+    ensure_published(&header, env.block.time)?;
+    ensure_not_expired(&header, env.block.time)?;
     let NodeHeader {
         created_by: tip_recipient,
         parent_id,
         ..
-    } = NODE_HEADER.load(deps.storage, &node_id)?;
+    } = header;
 
     // Currently number of likes received by the node
     let n_likes = NODE_NUM_LIKES.may_load(deps.storage, &node_id)?.unwrap_or_default();
@@ -29,11 +35,17 @@ pub fn exec_toggle_like(
     if IX_ADDR_2_LIKED_ID.has(deps.storage, (&info.sender, &node_id)) {
         // Sender already liked, so we unlike. Decrement or remove state data
         IX_LIKED_ID_2_ADDR.remove(deps.storage, (&node_id, &info.sender));
+        IX_ADDR_2_LIKED_ID.remove(deps.storage, (&info.sender, &node_id));
         if n_likes > u32::MIN {
             let next_n_likes = n_likes - 1;
             IX_PARENT_2_RANKED_CHILD_ID.remove(deps.storage, (&parent_id, n_likes, &node_id));
+            IX_PARENT_2_RANKED_CHILD_ID.save(deps.storage, (&parent_id, next_n_likes, &node_id), &0)?;
+            for tag in NODE_ATTRS.load(deps.storage, &node_id)?.tags.iter() {
+                let tag = tag.to_lowercase();
+                IX_TAG_2_NODE_ID.remove(deps.storage, (&tag, n_likes, &node_id));
+                IX_TAG_2_NODE_ID.save(deps.storage, (&tag, next_n_likes, &node_id), &0)?;
+            }
             if next_n_likes > 0 {
-                IX_PARENT_2_RANKED_CHILD_ID.save(deps.storage, (&parent_id, next_n_likes, &node_id), &0)?;
                 NODE_NUM_LIKES.save(deps.storage, &node_id, &next_n_likes)?;
             } else {
                 NODE_NUM_LIKES.remove(deps.storage, &node_id);
@@ -42,14 +54,16 @@ pub fn exec_toggle_like(
     } else {
         // Sender is liking
         IX_LIKED_ID_2_ADDR.save(deps.storage, (&node_id, &info.sender), &0)?;
+        IX_ADDR_2_LIKED_ID.save(deps.storage, (&info.sender, &node_id), &0)?;
         if n_likes < u32::MAX {
             let next_n_likes = n_likes + 1;
             IX_PARENT_2_RANKED_CHILD_ID.remove(deps.storage, (&parent_id, n_likes, &node_id));
             IX_PARENT_2_RANKED_CHILD_ID.save(deps.storage, (&parent_id, next_n_likes, &node_id), &0)?;
 
-            for tag in NODE_TAGS.load(deps.storage, &node_id)?.iter() {
-                IX_TAG_2_NODE_ID.remove(deps.storage, (tag, n_likes, &node_id));
-                IX_TAG_2_NODE_ID.save(deps.storage, (tag, next_n_likes, &node_id), &0)?;
+            for tag in NODE_ATTRS.load(deps.storage, &node_id)?.tags.iter() {
+                let tag = tag.to_lowercase();
+                IX_TAG_2_NODE_ID.remove(deps.storage, (&tag, n_likes, &node_id));
+                IX_TAG_2_NODE_ID.save(deps.storage, (&tag, next_n_likes, &node_id), &0)?;
             }
 
             NODE_NUM_LIKES.save(deps.storage, &node_id, &next_n_likes)?;
@@ -60,10 +74,18 @@ pub fn exec_toggle_like(
     //  - create relation <(post)--{like: nodeId}--(sender)>
 
     // Apply any included tip (for the "like" case)
+    let resp = take_funds(
+        Response::new().add_attribute("action", "like"),
+        &config,
+        &info,
+        &env.contract.address,
+        tip_amount,
+    )?;
     apply_tip_if_exists(
         deps.storage,
-        Response::new().add_attribute("action", "like"),
+        resp,
         &config,
+        Some(&info.sender),
         tip_recipient,
         tip_amount,
         &node_id,
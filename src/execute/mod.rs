@@ -1,9 +1,16 @@
+pub mod accept;
+pub mod bounty;
+pub mod claim;
 pub mod configure;
 pub mod delete;
 pub mod like;
+pub mod pin;
+pub mod prune;
 pub mod react;
 pub mod reply;
+pub mod reply_policy;
 pub mod tip;
+pub mod vote;
 
 use cosmwasm_std::{DepsMut, Env, MessageInfo};
 
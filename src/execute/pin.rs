@@ -0,0 +1,63 @@
+use crate::{
+    error::ContractError,
+    msg::PinMsg,
+    state::{CONFIG, DEFAULT_MAX_PINS, NODE_HEADER, OPERATOR, PINS},
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+/// Pin or unpin a node under its parent. Only the operator and the parent's
+/// author may do so.
+pub fn exec_toggle_pin(
+    ctx: Context,
+    msg: PinMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, info, .. } = ctx;
+    let PinMsg { node_id } = msg;
+
+    let parent_id = NODE_HEADER
+        .may_load(deps.storage, &node_id)?
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} not found", node_id),
+        })?
+        .parent_id;
+
+    let parent = NODE_HEADER
+        .may_load(deps.storage, &parent_id)?
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} has no parent to pin it under", node_id),
+        })?;
+
+    if info.sender != parent.created_by && info.sender != OPERATOR.load(deps.storage)? {
+        return Err(ContractError::NotAuthorized {
+            reason: "only the operator or the parent's author can pin".to_owned(),
+        });
+    }
+
+    let mut pinned_ids = PINS.may_load(deps.storage, &parent_id)?.unwrap_or_default();
+
+    let action = if pinned_ids.contains(&node_id) {
+        // Node already pinned, so we unpin it
+        pinned_ids.retain(|id| *id != node_id);
+        "unpin"
+    } else {
+        // Node is being pinned, after any already pinned
+        let max_pins = CONFIG.load(deps.storage)?.max_pins.unwrap_or(DEFAULT_MAX_PINS);
+        if pinned_ids.len() >= max_pins as usize {
+            return Err(ContractError::ValidationError {
+                reason: format!("max of {} pinned nodes reached", max_pins),
+            });
+        }
+        pinned_ids.push(node_id.to_owned());
+        "pin"
+    };
+
+    if pinned_ids.is_empty() {
+        PINS.remove(deps.storage, &parent_id);
+    } else {
+        PINS.save(deps.storage, &parent_id, &pinned_ids)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![attr("action", action), attr("node_id", node_id)]))
+}
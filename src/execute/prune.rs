@@ -0,0 +1,89 @@
+use crate::{
+    error::ContractError,
+    math::{add_u128, sub_u128},
+    msg::PruneMsg,
+    state::{
+        publish_scheduled_nodes, remove_node, CONFIG, IX_EXPIRY_2_NODE_ID, PRUNE_FUNDED_IDS, PRUNE_POOLS,
+        REMOVE_NODE_BATCH_SIZE,
+    },
+    tokens::TokenAmount,
+};
+use cosmwasm_std::{attr, Order, Response, StdResult};
+use cw_storage_plus::Bound;
+
+use super::Context;
+
+/// Max number of expired nodes pruned per Prune message
+pub const MAX_PRUNE_LIMIT: u16 = 50;
+
+pub fn exec_prune(
+    ctx: Context,
+    msg: PruneMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, info } = ctx;
+    let limit = msg.limit.min(MAX_PRUNE_LIMIT) as usize;
+    let config = CONFIG.load(deps.storage)?;
+
+    // Get the earliest nodes expiring at or before the current block time
+    let empty_id = String::new();
+    let max_bound = Bound::exclusive((env.block.time.nanos().saturating_add(1), &empty_id));
+    let expired = IX_EXPIRY_2_NODE_ID
+        .keys(deps.storage, None, Some(max_bound), Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(u64, String)>>>()?;
+
+    // Remove expired nodes, stopping at any with too many likes, reactions or
+    // votes to remove at once, which the next Prune resumes. A bounty is owed
+    // for each removed node whose creation fee was pooled, in the fee's token.
+    let mut n_pruned: u128 = 0;
+    let mut bounties: Vec<TokenAmount> = vec![];
+    for (expires_at, node_id) in expired.iter() {
+        if !remove_node(deps.storage, node_id, REMOVE_NODE_BATCH_SIZE)? {
+            break;
+        }
+        IX_EXPIRY_2_NODE_ID.remove(deps.storage, (*expires_at, node_id));
+        n_pruned += 1;
+        if let Some(token) = PRUNE_FUNDED_IDS.may_load(deps.storage, node_id)? {
+            PRUNE_FUNDED_IDS.remove(deps.storage, node_id);
+            let amount = config.prune_bounty.unwrap_or_default();
+            match bounties.iter_mut().find(|bounty| bounty.token == token) {
+                Some(bounty) => bounty.amount = add_u128(bounty.amount, amount)?,
+                None => bounties.push(TokenAmount { token, amount }),
+            }
+        }
+    }
+
+    // Also count published scheduled replies toward their parents
+    let published_attrs = publish_scheduled_nodes(deps.storage, env.block.time, limit)?;
+
+    let mut resp = Response::new()
+        .add_attributes(vec![attr("action", "prune"), attr("n_pruned", n_pruned.to_string())])
+        .add_attributes(published_attrs);
+
+    // Pay the sender the bounty in each token from the pool of creation fees
+    // paid in it, as far as it allows
+    for TokenAmount { token, amount } in bounties {
+        let token_key = token.to_key();
+        let pool = match PRUNE_POOLS.may_load(deps.storage, &token_key)? {
+            Some(pool) => pool.amount,
+            None => continue,
+        };
+        let amount = amount.min(pool);
+        if amount.is_zero() {
+            continue;
+        }
+        PRUNE_POOLS.save(
+            deps.storage,
+            &token_key,
+            &TokenAmount {
+                token: token.to_owned(),
+                amount: sub_u128(pool, amount)?,
+            },
+        )?;
+        resp = resp
+            .add_attribute("bounty", amount.to_string())
+            .add_submessage(token.transfer(&info.sender, amount)?);
+    }
+
+    Ok(resp)
+}
@@ -1,4 +1,9 @@
-use crate::{error::ContractError, msg::ReactMsg};
+use crate::{
+    error::ContractError,
+    math::{add_u32, sub_u32},
+    msg::ReactMsg,
+    state::{ensure_not_expired, ensure_published, IX_NODE_ADDR_2_REACTION, NODE_HEADER, NODE_STATS},
+};
 use cosmwasm_std::{attr, Response};
 
 use super::Context;
@@ -7,6 +12,26 @@ pub fn exec_toggle_reaction(
     ctx: Context,
     msg: ReactMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, .. } = ctx;
+    let Context { deps, env, info } = ctx;
+    let ReactMsg { node_id, reaction } = msg;
+    let header = NODE_HEADER.load(deps.storage, &node_id)?;
+    ensure_published(&header, env.block.time)?;
+    ensure_not_expired(&header, env.block.time)?;
+
+    let reaction_key = reaction.to_key();
+    let mut stats = NODE_STATS.load(deps.storage, &node_id)?;
+
+    if IX_NODE_ADDR_2_REACTION.has(deps.storage, (&node_id, &info.sender, &reaction_key)) {
+        // Sender already reacted this way, so we remove the reaction
+        IX_NODE_ADDR_2_REACTION.remove(deps.storage, (&node_id, &info.sender, &reaction_key));
+        stats.n_reactions = sub_u32(stats.n_reactions, 1)?;
+    } else {
+        // Sender is reacting
+        IX_NODE_ADDR_2_REACTION.save(deps.storage, (&node_id, &info.sender, &reaction_key), &reaction)?;
+        stats.n_reactions = add_u32(stats.n_reactions, 1)?;
+    }
+
+    NODE_STATS.save(deps.storage, &node_id, &stats)?;
+
     Ok(Response::new().add_attributes(vec![attr("action", "react")]))
 }
@@ -1,15 +1,52 @@
-use crate::{error::ContractError, msg::ReplyMsg, state::init_node};
+use crate::{
+    error::ContractError,
+    math::add_u128,
+    msg::ReplyMsg,
+    state::{init_node, CONFIG, PRUNE_FUNDED_IDS, PRUNE_POOLS},
+    tokens::TokenAmount,
+};
 use cosmwasm_std::{attr, Response};
 
-use super::Context;
+use super::{tip::take_funds, Context};
 
 pub fn exec_reply(
     ctx: Context,
     node_init_args: ReplyMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, info, env } = ctx;
+    let Context { mut deps, info, env } = ctx;
+    let config = CONFIG.load(deps.storage)?;
 
-    init_node(deps.storage, &info.sender, env.block.time, node_init_args)?;
+    // Charge expiring replies the creation fee, pooled by token to pay prune
+    // bounties
+    let is_expiring = node_init_args.expires_at.is_some() || config.node_ttl.is_some();
+    let fee = config.fees.creation;
+    let is_fee_due = is_expiring && config.prune_bounty.is_some() && !fee.is_zero();
 
-    Ok(Response::new().add_attributes(vec![attr("action", "reply")]))
+    let mut resp = Response::new().add_attributes(vec![attr("action", "reply")]);
+
+    if is_fee_due {
+        resp = take_funds(resp, &config, &info, &env.contract.address, fee)?;
+    }
+
+    let (node_id, node_attrs) = init_node(deps.branch(), &env, &info.sender, node_init_args)?;
+
+    if is_fee_due {
+        let token_key = config.token.to_key();
+        let pool = PRUNE_POOLS
+            .may_load(deps.storage, &token_key)?
+            .map(|pool| pool.amount)
+            .unwrap_or_default();
+        PRUNE_POOLS.save(
+            deps.storage,
+            &token_key,
+            &TokenAmount {
+                token: config.token.to_owned(),
+                amount: add_u128(pool, fee)?,
+            },
+        )?;
+        PRUNE_FUNDED_IDS.save(deps.storage, &node_id, &config.token)?;
+        resp = resp.add_attribute("creation_fee", fee.to_string());
+    }
+
+    Ok(resp.add_attributes(node_attrs))
 }
@@ -0,0 +1,45 @@
+use crate::{
+    error::ContractError,
+    msg::SetReplyPolicyMsg,
+    state::{NODE_ATTRS, NODE_HEADER},
+};
+use cosmwasm_std::{attr, Response};
+
+use super::Context;
+
+/// Change who may reply to a node & to its direct replies. Only the node's
+/// author may do so.
+pub fn exec_set_reply_policy(
+    ctx: Context,
+    msg: SetReplyPolicyMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, info, .. } = ctx;
+    let SetReplyPolicyMsg {
+        node_id,
+        reply_policy,
+        child_reply_policy,
+    } = msg;
+
+    let header = NODE_HEADER
+        .may_load(deps.storage, &node_id)?
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} not found", node_id),
+        })?;
+
+    if info.sender != header.created_by {
+        return Err(ContractError::NotAuthorized {
+            reason: "only the node's author can set its reply policy".to_owned(),
+        });
+    }
+
+    NODE_ATTRS.update(deps.storage, &node_id, |attrs| -> Result<_, ContractError> {
+        let mut attrs = attrs.ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} not found", node_id),
+        })?;
+        attrs.reply_policy = Some(reply_policy);
+        attrs.child_reply_policy = child_reply_policy;
+        Ok(attrs)
+    })?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "set_reply_policy"), attr("node_id", node_id)]))
+}
@@ -1,11 +1,16 @@
 use crate::{
     error::ContractError,
-    math::{add_u128, mul_ratio_u128},
-    models::{Config, FeeParams},
+    math::{add_u128, add_u32, mul_ratio_u128},
+    models::{Config, FeeParams, TipTotal},
     msg::TipMsg,
-    state::{NodeHeader, CONFIG, NODE_HEADER, NODE_ROYALTIES, ROYALTIES},
+    state::{
+        ensure_not_expired, ensure_published, NodeHeader, CLAIMABLE, CONFIG, IX_TOKEN_2_RANKED_EARNER,
+        IX_TOKEN_2_RANKED_TIPPER, NODE_HEADER, NODE_STATS, ROYALTIES, TIPS_GIVEN, TIPS_RECEIVED,
+    },
+    tokens::{Token, TokenAmount},
 };
-use cosmwasm_std::{attr, Addr, Response, Storage, Uint128};
+use cosmwasm_std::{attr, Addr, MessageInfo, Response, Storage, Uint128};
+use cw_storage_plus::Map;
 
 use super::Context;
 
@@ -13,28 +18,69 @@ pub fn exec_tip(
     ctx: Context,
     msg: TipMsg,
 ) -> Result<Response, ContractError> {
-    let Context { deps, .. } = ctx;
+    let Context { deps, env, info } = ctx;
     let TipMsg { node_id, tip_amount } = msg;
     let config = CONFIG.load(deps.storage)?;
+    let header = NODE_HEADER.load(deps.storage, &node_id)?;
+    ensure_published(&header, env.block.time)?;
+    ensure_not_expired(&header, env.block.time)?;
     let NodeHeader {
         created_by: tip_recipient,
         ..
-    } = NODE_HEADER.load(deps.storage, &node_id)?;
+    } = header;
+
+    let resp = take_funds(
+        Response::new().add_attribute("action", "tip"),
+        &config,
+        &info,
+        &env.contract.address,
+        tip_amount,
+    )?;
 
     apply_tip_if_exists(
         deps.storage,
-        Response::new().add_attribute("action", "tip"),
+        resp,
         &config,
+        Some(&info.sender),
         tip_recipient,
         tip_amount,
         &node_id,
     )
 }
 
+/// Take an amount of the config token from the sender. Native funds must be
+/// sent with the message, while cw20 funds are transferred from the sender
+/// under an allowance, ahead of any transfers added to the response after.
+pub fn take_funds(
+    resp: Response,
+    config: &Config,
+    info: &MessageInfo,
+    contract: &Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Ok(resp);
+    }
+    match &config.token {
+        Token::Denom(denom) => {
+            if !config.token.has_in_funds(&info.funds, Some(amount)) {
+                return Err(ContractError::ValidationError {
+                    reason: format!("expected {}{} in funds", amount, denom),
+                });
+            }
+            Ok(resp)
+        },
+        Token::Address(_) => Ok(resp.add_submessage(config.token.transfer_from(&info.sender, contract, amount)?)),
+    }
+}
+
+/// Pay a tip, or a bounty payout, to a node's author, less any fee. Bounty
+/// payouts have no tipper & aren't tips, so are left out of tip totals.
 pub fn apply_tip_if_exists(
     store: &mut dyn Storage,
     resp: Response,
     config: &Config,
+    tipper: Option<&Addr>,
     tip_recipient: Addr,
     tip_amount: Uint128,
     node_id: &String,
@@ -43,26 +89,108 @@ pub fn apply_tip_if_exists(
     ROYALTIES.update(store, |n| -> Result<_, ContractError> { add_u128(n, tip_amount) })?;
 
     // Increment node-specific tip amount
-    NODE_ROYALTIES.update(store, node_id, |n| -> Result<_, ContractError> {
-        add_u128(n.unwrap_or_default(), tip_amount)
+    NODE_STATS.update(store, node_id, |stats| -> Result<_, ContractError> {
+        let mut stats = stats.ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} not found", node_id),
+        })?;
+        stats.royalties = add_u128(stats.royalties, tip_amount)?;
+        Ok(stats)
     })?;
 
+    // Increment per-address tip totals
+    if let Some(tipper) = tipper.filter(|_| !tip_amount.is_zero()) {
+        record_tip(
+            store,
+            config,
+            TIPS_RECEIVED,
+            IX_TOKEN_2_RANKED_EARNER,
+            &tip_recipient,
+            tip_amount,
+        )?;
+        record_tip(store, config, TIPS_GIVEN, IX_TOKEN_2_RANKED_TIPPER, tipper, tip_amount)?;
+    }
+
     // Calc fee and node-creator royalties, adding transfer msgs to Response
-    let (royalty_amount, fee_amount) = process_tip_amount(tip_amount, &config)?;
+    let (royalty_amount, fee_amount) = process_tip_amount(tip_amount, config)?;
     let mut resp = resp;
 
     if !fee_amount.is_zero() {
         // We can unwrap fee_recipient following process_tip_amount
         let fee_recipient = config.fee_recipient.to_owned().unwrap();
-        resp = resp
-            .add_submessage(config.token.transfer(&fee_recipient, fee_amount)?)
+        resp = pay_out(store, resp, config, &fee_recipient, fee_amount)?
             .add_attribute("fee_amount", fee_amount.to_string())
             .add_attribute("fee_recipient", fee_recipient.to_string());
     }
 
-    Ok(resp
-        .add_attributes(vec![attr("royalty_amount", royalty_amount.to_string())])
-        .add_submessage(config.token.transfer(&tip_recipient, royalty_amount)?))
+    pay_out(
+        store,
+        resp.add_attributes(vec![attr("royalty_amount", royalty_amount.to_string())]),
+        config,
+        &tip_recipient,
+        royalty_amount,
+    )
+}
+
+/// Add a tip to an address's running total in the config token, re-ranking
+/// the address in the token's leaderboard
+fn record_tip(
+    store: &mut dyn Storage,
+    config: &Config,
+    totals: Map<(&Addr, &String), TipTotal>,
+    ranked_index: Map<(&String, u128, &Addr), u8>,
+    addr: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let token_key = config.token.to_key();
+    let total = totals.may_load(store, (addr, &token_key))?.unwrap_or_else(|| TipTotal {
+        token: config.token.to_owned(),
+        amount: Uint128::zero(),
+        n_tips: 0,
+    });
+    let next_total = TipTotal {
+        amount: add_u128(total.amount, amount)?,
+        n_tips: add_u32(total.n_tips, 1)?,
+        ..total
+    };
+
+    ranked_index.remove(store, (&token_key, total.amount.u128(), addr));
+    ranked_index.save(store, (&token_key, next_total.amount.u128(), addr), &0)?;
+    totals.save(store, (addr, &token_key), &next_total)?;
+
+    Ok(())
+}
+
+/// Transfer an amount to the recipient or, if tips are claimable, credit it
+/// to their claimable balance. Callers must first have taken the funds paid
+/// out, as with take_funds, or hold them in escrow.
+pub fn pay_out(
+    store: &mut dyn Storage,
+    resp: Response,
+    config: &Config,
+    recipient: &Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Ok(resp);
+    }
+    if !config.claimable_tips.unwrap_or_default() {
+        return Ok(resp.add_submessage(config.token.transfer(recipient, amount)?));
+    }
+    CLAIMABLE.update(
+        store,
+        (recipient, &config.token.to_key()),
+        |balance| -> Result<_, ContractError> {
+            let balance = balance.unwrap_or_else(|| TokenAmount {
+                token: config.token.to_owned(),
+                amount: Uint128::zero(),
+            });
+            Ok(TokenAmount {
+                amount: add_u128(balance.amount, amount)?,
+                ..balance
+            })
+        },
+    )?;
+    Ok(resp)
 }
 
 fn process_tip_amount(
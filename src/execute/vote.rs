@@ -0,0 +1,106 @@
+use crate::{
+    error::ContractError,
+    math::{add_u128, add_u32},
+    models::{NodeStatus, Poll, VoteWeighting},
+    msg::VoteMsg,
+    state::{
+        ensure_not_expired, ensure_published, PollVote, CONFIG, NODE_ATTRS, NODE_HEADER, NODE_STATS, POLL_TALLIES,
+        POLL_VOTES,
+    },
+};
+use cosmwasm_std::{attr, Response, Uint128};
+
+use super::Context;
+
+pub fn exec_vote(
+    ctx: Context,
+    msg: VoteMsg,
+) -> Result<Response, ContractError> {
+    let Context { deps, env, info } = ctx;
+    let VoteMsg { node_id, choices } = msg;
+
+    let poll = NODE_ATTRS
+        .may_load(deps.storage, &node_id)?
+        .and_then(|attrs| attrs.poll)
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} has no poll", node_id),
+        })?;
+
+    let header = NODE_HEADER.load(deps.storage, &node_id)?;
+    ensure_not_expired(&header, env.block.time)?;
+    ensure_published(&header, env.block.time)?;
+
+    if NODE_STATS.load(deps.storage, &node_id)?.status == NodeStatus::Deleted {
+        return Err(ContractError::ValidationError {
+            reason: format!("node {} is deleted", node_id),
+        });
+    }
+
+    if env.block.time >= poll.closes_at {
+        return Err(ContractError::ValidationError {
+            reason: "poll is closed".to_owned(),
+        });
+    }
+
+    if POLL_VOTES.has(deps.storage, (&node_id, &info.sender)) {
+        return Err(ContractError::ValidationError {
+            reason: "sender already voted".to_owned(),
+        });
+    }
+
+    validate_choices(&poll, &choices)?;
+
+    // Weight the vote by the sender's current token balance if required
+    let weight = match poll.weighting {
+        VoteWeighting::PerAddress => Uint128::one(),
+        VoteWeighting::TokenBalance => {
+            let config = CONFIG.load(deps.storage)?;
+            let balance = config.token.query_balance(deps.querier, &info.sender)?;
+            if balance.is_zero() {
+                return Err(ContractError::ValidationError {
+                    reason: "sender has no voting power".to_owned(),
+                });
+            }
+            balance
+        },
+    };
+
+    // Add the vote to each selected option's tally
+    let mut tally = POLL_TALLIES.load(deps.storage, &node_id)?;
+    tally.n_voters = add_u32(tally.n_voters, 1)?;
+    for i in choices.iter() {
+        tally.weights[*i as usize] = add_u128(tally.weights[*i as usize], weight)?;
+    }
+
+    POLL_TALLIES.save(deps.storage, &node_id, &tally)?;
+    POLL_VOTES.save(deps.storage, (&node_id, &info.sender), &PollVote { choices, weight })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "vote"),
+        attr("node_id", node_id),
+        attr("weight", weight.to_string()),
+    ]))
+}
+
+/// Ensure the choices are distinct, valid option indices, with only one
+/// choice allowed in single-choice polls
+fn validate_choices(
+    poll: &Poll,
+    choices: &[u32],
+) -> Result<(), ContractError> {
+    let reason = if choices.is_empty() {
+        Some("no options chosen".to_owned())
+    } else if !poll.multiple_choice && choices.len() > 1 {
+        Some("poll allows only one choice".to_owned())
+    } else if let Some(i) = choices.iter().find(|i| **i as usize >= poll.options.len()) {
+        Some(format!("invalid poll option {}", i))
+    } else if choices.iter().enumerate().any(|(i, c)| choices[..i].contains(c)) {
+        Some("poll options chosen more than once".to_owned())
+    } else {
+        None
+    };
+    match reason {
+        Some(reason) => Err(ContractError::ValidationError { reason }),
+        None => Ok(()),
+    }
+}
@@ -8,23 +8,27 @@ pub fn compute_node_cost(
     fees: &FeeParams,
     is_update: bool,
     body_len: usize,
+    has_body_ref: bool,
     n_tags: usize,
     n_links: usize,
 ) -> Result<(Uint128, NodeCostSubtotals), ContractError> {
     let tag_fee = mul_u128(fees.tag, n_tags as u128)?;
     let link_fee = mul_u128(fees.link, n_links as u128)?;
     let text_fee = mul_u128(fees.text, (body_len / UNIT_TEXT_LENGTH) as u128)?;
-    let creation_fee = if is_update {
-        Uint128::zero()
+
+    // Bodies stored off chain by reference are charged a flat fee instead
+    let body_fee = if has_body_ref {
+        fees.body_ref.unwrap_or_default()
     } else {
-        Uint128::from(fees.creation)
+        text_fee
     };
+    let creation_fee = if is_update { Uint128::zero() } else { fees.creation };
 
     Ok((
-        tag_fee + link_fee + text_fee + creation_fee,
+        tag_fee + link_fee + body_fee + creation_fee,
         NodeCostSubtotals {
             creation: creation_fee,
-            body: text_fee,
+            body: body_fee,
             tags: tag_fee,
             links: link_fee,
         },
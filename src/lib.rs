@@ -1,16 +1,21 @@
 #[cfg(not(feature = "library"))]
 pub mod contract;
+pub mod cursor;
 pub mod error;
 #[cfg(not(feature = "library"))]
 pub mod execute;
 pub mod fees;
 mod math;
+#[cfg(not(feature = "library"))]
+mod migrations;
 pub mod models;
 pub mod msg;
 #[cfg(not(feature = "library"))]
 pub mod query;
+pub mod sanitize;
 pub mod state;
 pub mod tokens;
+pub mod validation;
 
 #[cfg(not(target_arch = "wasm32"))]
 mod interface;
@@ -0,0 +1,543 @@
+use cosmwasm_std::{Addr, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::Map;
+
+use crate::{
+    error::ContractError,
+    models::Label,
+    state::{
+        NodeAttributes, NodeContent, NodeHeader, NodeStats, COUNTERS, IX_ADDR_2_LIKED_ID, IX_LIKED_ID_2_ADDR,
+        NODE_ATTRS, NODE_CONTENT, NODE_HEADER, NODE_ID_COUNTER_KEY, NODE_STATS,
+    },
+};
+
+/// Next node ID to migrate, for each migration left in progress by a previous
+/// batch
+const MIGRATION_CURSORS: Map<&str, u64> = Map::new("migration_cursors");
+
+/// Last node ID to migrate, for each migration in progress. Fixed when the
+/// migration starts, as nodes created between batches are already in the
+/// current format.
+const MIGRATION_END_IDS: Map<&str, u64> = Map::new("migration_end_ids");
+
+const NODE_STORAGE_MIGRATION: &str = "node_storage";
+const NODE_LABELS_MIGRATION: &str = "node_labels";
+const LIKED_IDS_MIGRATION: &str = "liked_ids";
+
+/// Max number of nodes migrated per migrate call when not given
+pub const DEFAULT_MIGRATION_BATCH_SIZE: u64 = 500;
+
+/// Outcome of migrating one batch of nodes
+pub struct MigrationProgress {
+    pub n_migrated: u64,
+    /// Whether no nodes are left to migrate
+    pub is_complete: bool,
+}
+
+/// Per-node storage layout prior to consolidating node data into header,
+/// stats, attributes and content records.
+mod legacy {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{Addr, Timestamp, Uint128};
+    use cw_storage_plus::Map;
+
+    use crate::models::{Link, NodeStatus, Poll, QuoteRef};
+
+    pub const NODE_HEADER: Map<&String, NodeHeader> = Map::new("nh");
+    pub const NODE_STATUS: Map<&String, NodeStatus> = Map::new("node_status");
+    pub const NODE_ATTRS: Map<&String, NodeAttributes> = Map::new("node_attrs");
+    pub const NODE_TAGS: Map<&String, Vec<String>> = Map::new("node_tags");
+    pub const NODE_UPDATED_AT: Map<&String, Timestamp> = Map::new("node_t_updated");
+    pub const NODE_NUM_REPLIES: Map<&String, u16> = Map::new("n_replies");
+    pub const NODE_NUM_REACTIONS: Map<&String, u16> = Map::new("n_reacts");
+    pub const NODE_ROYALTIES: Map<&String, Uint128> = Map::new("node_royalties");
+
+    #[cw_serde]
+    pub struct NodeHeader {
+        pub id: String,
+        pub parent_id: String,
+        pub created_by: Addr,
+    }
+
+    #[cw_serde]
+    pub struct NodeAttributes {
+        pub created_at: Timestamp,
+        pub title: String,
+        pub body: Option<String>,
+        pub links: Vec<Link>,
+        pub nsfw: bool,
+    }
+
+    /// Consolidated node attributes prior to replacing the nsfw flag with
+    /// content labels
+    pub const NSFW_NODE_ATTRS: Map<&String, NsfwNodeAttributes> = Map::new("na");
+
+    #[cw_serde]
+    pub struct NsfwNodeAttributes {
+        pub title: String,
+        pub tags: Vec<String>,
+        pub nsfw: bool,
+        pub quote: Option<QuoteRef>,
+        pub poll: Option<Poll>,
+    }
+}
+
+/// Move per-node data out of the legacy maps into the consolidated header,
+/// stats, attributes & content records, widening the legacy u16 reply and
+/// reaction counts to u32. Migrates up to `limit` nodes, resuming after the
+/// last batch, and does nothing if the root node has already been migrated
+/// with no batch in progress.
+///
+/// Stats records written with u16 counts need no rewrite, as they are stored
+/// as JSON numbers, which deserialize into the wider type unchanged.
+pub fn migrate_node_storage(
+    store: &mut dyn Storage,
+    limit: u64,
+) -> Result<MigrationProgress, ContractError> {
+    let root_id = "1".to_owned();
+    let is_started = legacy::NODE_STATUS.has(store, &root_id);
+    let (ids, is_complete) = match next_batch(store, NODE_STORAGE_MIGRATION, is_started, limit)? {
+        Some(batch) => batch,
+        None => return Ok(MigrationProgress::complete()),
+    };
+    let mut n_migrated = 0u64;
+
+    for id in ids.map(|id| id.to_string()) {
+        let legacy::NodeHeader {
+            parent_id, created_by, ..
+        } = match legacy::NODE_HEADER.may_load(store, &id) {
+            Ok(Some(header)) => header,
+            Ok(None) => continue,
+            // Skip nodes already saved in the current format
+            Err(_) if NODE_HEADER.load(store, &id).is_ok() => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let legacy::NodeAttributes {
+            created_at,
+            title,
+            body,
+            links,
+            nsfw,
+        } = legacy::NODE_ATTRS.load(store, &id)?;
+
+        // Parents have lower ID's than their children, so they've already
+        // been migrated by the time we compute a child's depth.
+        let depth = match NODE_HEADER.may_load(store, &parent_id)? {
+            Some(parent) => parent.depth + 1,
+            None => 0,
+        };
+
+        NODE_HEADER.save(
+            store,
+            &id,
+            &NodeHeader {
+                id: id.to_owned(),
+                parent_id,
+                depth,
+                created_by,
+                created_at,
+                publish_at: None,
+                expires_at: None,
+            },
+        )?;
+
+        let stats = NodeStats {
+            status: legacy::NODE_STATUS.load(store, &id)?,
+            updated_at: legacy::NODE_UPDATED_AT.may_load(store, &id)?.unwrap_or(created_at),
+            n_replies: legacy::NODE_NUM_REPLIES
+                .may_load(store, &id)?
+                .unwrap_or_default()
+                .into(),
+            n_reactions: legacy::NODE_NUM_REACTIONS
+                .may_load(store, &id)?
+                .unwrap_or_default()
+                .into(),
+            royalties: legacy::NODE_ROYALTIES.may_load(store, &id)?.unwrap_or(Uint128::zero()),
+        };
+
+        let attrs = NodeAttributes {
+            title,
+            tags: legacy::NODE_TAGS.may_load(store, &id)?.unwrap_or_default(),
+            labels: to_labels(nsfw),
+            lang: None,
+            reply_policy: None,
+            child_reply_policy: None,
+            kind: None,
+            quote: None,
+            poll: None,
+        };
+
+        NODE_STATS.save(store, &id, &stats)?;
+        NODE_ATTRS.save(store, &id, &attrs)?;
+
+        NODE_CONTENT.save(
+            store,
+            &id,
+            &NodeContent {
+                body,
+                body_ref: None,
+                links,
+                translations: None,
+            },
+        )?;
+
+        legacy::NODE_STATUS.remove(store, &id);
+        legacy::NODE_ATTRS.remove(store, &id);
+        legacy::NODE_TAGS.remove(store, &id);
+        legacy::NODE_UPDATED_AT.remove(store, &id);
+        legacy::NODE_NUM_REPLIES.remove(store, &id);
+        legacy::NODE_NUM_REACTIONS.remove(store, &id);
+        legacy::NODE_ROYALTIES.remove(store, &id);
+
+        n_migrated += 1;
+    }
+
+    if is_complete {
+        // The root node's empty parent ID also had a reply count
+        legacy::NODE_NUM_REPLIES.remove(store, &"".to_owned());
+    }
+
+    Ok(MigrationProgress {
+        n_migrated,
+        is_complete,
+    })
+}
+
+/// Replace the nsfw flag in consolidated node attributes with content labels.
+/// Migrates up to `limit` nodes, resuming after the last batch, and does
+/// nothing if the root node's attributes already have labels with no batch
+/// in progress.
+pub fn migrate_node_labels(
+    store: &mut dyn Storage,
+    limit: u64,
+) -> Result<MigrationProgress, ContractError> {
+    let root_id = "1".to_owned();
+    let is_started = legacy::NSFW_NODE_ATTRS.load(store, &root_id).is_ok();
+    let (ids, is_complete) = match next_batch(store, NODE_LABELS_MIGRATION, is_started, limit)? {
+        Some(batch) => batch,
+        None => return Ok(MigrationProgress::complete()),
+    };
+    let mut n_migrated = 0u64;
+
+    for id in ids.map(|id| id.to_string()) {
+        let legacy::NsfwNodeAttributes {
+            title,
+            tags,
+            nsfw,
+            quote,
+            poll,
+        } = match legacy::NSFW_NODE_ATTRS.may_load(store, &id) {
+            Ok(Some(attrs)) => attrs,
+            Ok(None) => continue,
+            // Skip nodes already saved in the current format
+            Err(_) if NODE_ATTRS.load(store, &id).is_ok() => continue,
+            Err(e) => return Err(e.into()),
+        };
+
+        NODE_ATTRS.save(
+            store,
+            &id,
+            &NodeAttributes {
+                title,
+                tags,
+                labels: to_labels(nsfw),
+                lang: None,
+                reply_policy: None,
+                child_reply_policy: None,
+                kind: None,
+                quote,
+                poll,
+            },
+        )?;
+
+        n_migrated += 1;
+    }
+
+    Ok(MigrationProgress {
+        n_migrated,
+        is_complete,
+    })
+}
+
+/// Index each node's likers by address, which likes made before unliking was
+/// possible weren't. Migrates up to `limit` nodes, resuming after the last
+/// batch, and only starts if no like is indexed by address yet.
+pub fn migrate_liked_ids(
+    store: &mut dyn Storage,
+    limit: u64,
+) -> Result<MigrationProgress, ContractError> {
+    let is_started = IX_ADDR_2_LIKED_ID.is_empty(store) && !IX_LIKED_ID_2_ADDR.is_empty(store);
+    let (ids, is_complete) = match next_batch(store, LIKED_IDS_MIGRATION, is_started, limit)? {
+        Some(batch) => batch,
+        None => return Ok(MigrationProgress::complete()),
+    };
+    let mut n_migrated = 0u64;
+
+    for id in ids.map(|id| id.to_string()) {
+        let likers = IX_LIKED_ID_2_ADDR
+            .prefix(&id)
+            .keys(store, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<Addr>>>()?;
+        for addr in likers.iter() {
+            IX_ADDR_2_LIKED_ID.save(store, (addr, &id), &0)?;
+        }
+        if !likers.is_empty() {
+            n_migrated += 1;
+        }
+    }
+
+    Ok(MigrationProgress {
+        n_migrated,
+        is_complete,
+    })
+}
+
+impl MigrationProgress {
+    fn complete() -> Self {
+        Self {
+            n_migrated: 0,
+            is_complete: true,
+        }
+    }
+}
+
+/// Get the range of node ID's in the migration's next batch, saving a cursor
+/// to resume from if nodes remain after it, along with whether it's the last.
+/// Only nodes that existed when the migration started are visited. Returns
+/// None if the migration is neither in progress nor newly `started`.
+fn next_batch(
+    store: &mut dyn Storage,
+    migration: &str,
+    is_started: bool,
+    limit: u64,
+) -> Result<Option<(std::ops::RangeInclusive<u64>, bool)>, ContractError> {
+    let (start_id, max_id) = match MIGRATION_CURSORS.may_load(store, migration)? {
+        Some(id) => (id, MIGRATION_END_IDS.load(store, migration)?),
+        None if is_started => (
+            1,
+            COUNTERS.may_load(store, NODE_ID_COUNTER_KEY)?.unwrap_or_default().u64(),
+        ),
+        None => return Ok(None),
+    };
+    let end_id = max_id.min(start_id.saturating_add(limit.max(1)) - 1);
+    let is_complete = end_id >= max_id;
+
+    if is_complete {
+        MIGRATION_CURSORS.remove(store, migration);
+        MIGRATION_END_IDS.remove(store, migration);
+    } else {
+        MIGRATION_CURSORS.save(store, migration, &(end_id + 1))?;
+        MIGRATION_END_IDS.save(store, migration, &max_id)?;
+    }
+
+    Ok(Some((start_id..=end_id, is_complete)))
+}
+
+fn to_labels(nsfw: bool) -> Vec<Label> {
+    if nsfw {
+        vec![Label::Nsfw]
+    } else {
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{testing::MockStorage, Addr, Timestamp, Uint64};
+
+    use super::*;
+    use crate::{models::NodeStatus, state::increment_counter};
+
+    /// Seed a thread of nodes in the legacy layout, each replying to the last
+    fn seed_legacy_thread(
+        store: &mut dyn Storage,
+        n_nodes: u64,
+    ) {
+        for i in 1..=n_nodes {
+            let id = i.to_string();
+            let parent_id = if i == 1 { String::new() } else { (i - 1).to_string() };
+            legacy::NODE_HEADER
+                .save(
+                    store,
+                    &id,
+                    &legacy::NodeHeader {
+                        id: id.to_owned(),
+                        parent_id,
+                        created_by: Addr::unchecked(format!("author{}", i)),
+                    },
+                )
+                .unwrap();
+            legacy::NODE_STATUS.save(store, &id, &NodeStatus::Normal).unwrap();
+            legacy::NODE_ATTRS
+                .save(
+                    store,
+                    &id,
+                    &legacy::NodeAttributes {
+                        created_at: Timestamp::from_seconds(i),
+                        title: format!("Node {}", i),
+                        body: Some("body".to_owned()),
+                        links: vec![],
+                        nsfw: i % 2 == 0,
+                    },
+                )
+                .unwrap();
+            legacy::NODE_TAGS.save(store, &id, &vec!["tag".to_owned()]).unwrap();
+            legacy::NODE_NUM_REPLIES.save(store, &id, &u16::MAX).unwrap();
+            legacy::NODE_NUM_REACTIONS.save(store, &id, &u16::MAX).unwrap();
+            legacy::NODE_ROYALTIES
+                .save(store, &id, &Uint128::new(i as u128))
+                .unwrap();
+        }
+        legacy::NODE_NUM_REPLIES.save(store, &String::new(), &1).unwrap();
+        COUNTERS
+            .save(store, NODE_ID_COUNTER_KEY, &Uint64::new(n_nodes))
+            .unwrap();
+    }
+
+    #[test]
+    fn migrates_node_storage_in_batches() {
+        let mut store = MockStorage::new();
+        seed_legacy_thread(&mut store, 5);
+
+        let progress = migrate_node_storage(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (2, false));
+        let progress = migrate_node_storage(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (2, false));
+        let progress = migrate_node_storage(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (1, true));
+
+        for i in 1..=5u64 {
+            let id = i.to_string();
+            let header = NODE_HEADER.load(&store, &id).unwrap();
+            assert_eq!(header.depth, (i - 1) as u32);
+            assert_eq!(header.created_by, Addr::unchecked(format!("author{}", i)));
+            assert_eq!(header.created_at, Timestamp::from_seconds(i));
+
+            let stats = NODE_STATS.load(&store, &id).unwrap();
+            assert_eq!(stats.n_replies, u16::MAX as u32);
+            assert_eq!(stats.n_reactions, u16::MAX as u32);
+            assert_eq!(stats.royalties, Uint128::new(i as u128));
+            assert_eq!(stats.updated_at, Timestamp::from_seconds(i));
+
+            let attrs = NODE_ATTRS.load(&store, &id).unwrap();
+            assert_eq!(attrs.title, format!("Node {}", i));
+            assert_eq!(attrs.tags, vec!["tag".to_owned()]);
+            assert_eq!(attrs.labels, to_labels(i % 2 == 0));
+
+            let content = NODE_CONTENT.load(&store, &id).unwrap();
+            assert_eq!(content.body, Some("body".to_owned()));
+
+            assert!(!legacy::NODE_STATUS.has(&store, &id));
+            assert!(!legacy::NODE_NUM_REPLIES.has(&store, &id));
+        }
+        assert!(!legacy::NODE_NUM_REPLIES.has(&store, &String::new()));
+        assert!(!MIGRATION_CURSORS.has(&store, NODE_STORAGE_MIGRATION));
+
+        // Migrating again does nothing
+        let progress = migrate_node_storage(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (0, true));
+    }
+
+    #[test]
+    fn skips_nodes_created_between_batches() {
+        let mut store = MockStorage::new();
+        seed_legacy_thread(&mut store, 4);
+
+        let progress = migrate_node_storage(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (2, false));
+
+        // Reply to a migrated node, as a Reply between batches would
+        let id = increment_counter(&mut store, NODE_ID_COUNTER_KEY, 1u64)
+            .unwrap()
+            .to_string();
+        let header = NodeHeader {
+            id: id.to_owned(),
+            parent_id: "2".to_owned(),
+            depth: 2,
+            created_by: Addr::unchecked("replier"),
+            created_at: Timestamp::from_seconds(10),
+            publish_at: None,
+            expires_at: None,
+        };
+        NODE_HEADER.save(&mut store, &id, &header).unwrap();
+        let attrs = NodeAttributes {
+            title: "Reply".to_owned(),
+            tags: vec![],
+            labels: vec![Label::Spoiler],
+            lang: None,
+            reply_policy: None,
+            child_reply_policy: None,
+            kind: None,
+            quote: None,
+            poll: None,
+        };
+        NODE_ATTRS.save(&mut store, &id, &attrs).unwrap();
+
+        // Only nodes existing at the start are migrated
+        let progress = migrate_node_storage(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (2, true));
+        assert_eq!(NODE_HEADER.load(&store, &id).unwrap(), header);
+
+        // Later migrations leave the new node as is
+        let progress = migrate_node_labels(&mut store, 10).unwrap();
+        assert!(progress.is_complete);
+        assert_eq!(NODE_ATTRS.load(&store, &id).unwrap(), attrs);
+    }
+
+    #[test]
+    fn indexes_existing_likes_by_address() {
+        let mut store = MockStorage::new();
+        COUNTERS.save(&mut store, NODE_ID_COUNTER_KEY, &Uint64::new(3)).unwrap();
+        for (id, addr) in [("1", "a"), ("1", "b"), ("3", "a")] {
+            IX_LIKED_ID_2_ADDR
+                .save(&mut store, (&id.to_owned(), &Addr::unchecked(addr)), &0)
+                .unwrap();
+        }
+
+        let progress = migrate_liked_ids(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (1, false));
+        let progress = migrate_liked_ids(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (1, true));
+
+        for (id, addr) in [("1", "a"), ("1", "b"), ("3", "a")] {
+            assert!(IX_ADDR_2_LIKED_ID.has(&store, (&Addr::unchecked(addr), &id.to_owned())));
+        }
+
+        // Once likes are indexed by address, migrating again does nothing
+        let progress = migrate_liked_ids(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (0, true));
+    }
+
+    #[test]
+    fn migrates_node_labels_in_batches() {
+        let mut store = MockStorage::new();
+        for i in 1..=3u64 {
+            legacy::NSFW_NODE_ATTRS
+                .save(
+                    &mut store,
+                    &i.to_string(),
+                    &legacy::NsfwNodeAttributes {
+                        title: format!("Node {}", i),
+                        tags: vec![],
+                        nsfw: i == 2,
+                        quote: None,
+                        poll: None,
+                    },
+                )
+                .unwrap();
+        }
+        COUNTERS.save(&mut store, NODE_ID_COUNTER_KEY, &Uint64::new(3)).unwrap();
+
+        let progress = migrate_node_labels(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (2, false));
+        let progress = migrate_node_labels(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (1, true));
+
+        for i in 1..=3u64 {
+            let attrs = NODE_ATTRS.load(&store, &i.to_string()).unwrap();
+            assert_eq!(attrs.labels, to_labels(i == 2));
+        }
+
+        let progress = migrate_node_labels(&mut store, 2).unwrap();
+        assert_eq!((progress.n_migrated, progress.is_complete), (0, true));
+    }
+}
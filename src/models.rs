@@ -7,6 +7,8 @@ use crate::tokens::Token;
 pub enum NodeStatus {
     Normal,
     Deleted,
+    /// Node is visible but accepts no further replies
+    Locked,
 }
 
 #[cw_serde]
@@ -17,6 +19,89 @@ pub struct Config {
     pub fee_recipient: Option<Addr>,
     /// Prices and fee rates for various actions
     pub fees: FeeParams,
+    /// Max nesting depth of replies below the root node, if any
+    pub max_depth: Option<u32>,
+    /// Size & format limits on node content, if any
+    pub limits: Option<ContentLimits>,
+    /// Allowed URL schemes & media providers for node links, if any
+    pub links: Option<LinkPolicy>,
+    /// How to handle disallowed markup in titles & bodies. Defaults to Reject.
+    pub sanitize_mode: Option<SanitizeMode>,
+    /// Seconds after creation at which replies expire, if any
+    pub node_ttl: Option<u64>,
+    /// Amount of the config token paid to the sender of Prune for each
+    /// expired node pruned. Paid from the creation fees of expiring replies,
+    /// which are only charged while this is set.
+    pub prune_bounty: Option<Uint128>,
+    /// Max number of pinned children per parent node. Defaults to 3.
+    pub max_pins: Option<u32>,
+    /// What happens to the unawarded part of a bounty after its deadline.
+    /// Defaults to Refund.
+    pub bounty_fallback: Option<BountyFallback>,
+    /// Credit tip royalties & fees to recipients' claimable balances instead
+    /// of transferring them on each tip
+    pub claimable_tips: Option<bool>,
+}
+
+#[cw_serde]
+pub enum BountyFallback {
+    /// Return it to funders in proportion to their contributions
+    Refund,
+    /// Split it among the node's most liked replies in proportion to likes
+    SplitByLikes,
+}
+
+#[cw_serde]
+pub enum SanitizeMode {
+    /// Fail on disallowed markup
+    Reject,
+    /// Remove disallowed markup before saving
+    Strip,
+}
+
+#[cw_serde]
+pub struct ContentLimits {
+    /// Max number of chars in a node title
+    pub max_title_length: Option<u32>,
+    /// Max number of chars in a node body
+    pub max_body_length: Option<u32>,
+    /// Max number of tags per node
+    pub max_tags: Option<u32>,
+    /// Max number of links per node
+    pub max_links: Option<u32>,
+    /// Max number of mentioned addresses per node
+    pub max_mentions: Option<u32>,
+    /// Max number of options in a poll
+    pub max_poll_options: Option<u32>,
+    /// Max number of translations per node
+    pub max_translations: Option<u32>,
+    /// Max number of chars in each tag
+    pub max_tag_length: Option<u32>,
+    /// Set of all chars allowed in tags
+    pub tag_charset: Option<String>,
+}
+
+#[cw_serde]
+pub struct LinkPolicy {
+    /// URL schemes allowed in links. If None, http and https are allowed.
+    pub schemes: Option<Vec<String>>,
+    /// Providers allowed in video links. If None, any provider is allowed.
+    pub video_providers: Option<Vec<MediaProvider>>,
+    /// Providers allowed in audio links. If None, any provider is allowed.
+    pub audio_providers: Option<Vec<MediaProvider>>,
+    /// Hosts allowed in image links. If None, any host is allowed.
+    pub image_hosts: Option<Vec<String>>,
+    /// Query each NFT link's contract to ensure the token exists
+    pub verify_nfts: Option<bool>,
+}
+
+#[cw_serde]
+pub struct MediaProvider {
+    /// Provider name, as given in a link, e.g. "youtube"
+    pub name: String,
+    /// Hosts from which the provider's media is embedded, e.g. "youtube.com".
+    /// Subdomains of each host are also allowed.
+    pub hosts: Vec<String>,
 }
 
 #[cw_serde]
@@ -33,6 +118,9 @@ pub struct FeeParams {
     pub tag: Uint128,
     /// Fee rate applied to tips
     pub tip_pct: Uint128,
+    /// Flat cost of a body stored off chain by reference, charged instead of
+    /// the text fee
+    pub body_ref: Option<Uint128>,
 }
 
 #[cw_serde]
@@ -42,10 +130,12 @@ pub struct Node {
     pub status: NodeStatus,
     /// Parent node ID
     pub parent_id: String,
+    /// Nesting depth below the root node
+    pub depth: u32,
     /// Number of child nodes
-    pub n_replies: u16,
+    pub n_replies: u32,
     /// Number of reactions to the node
-    pub n_reactions: u16,
+    pub n_reactions: u32,
     /// Total tip amount received by node creator
     pub royalties: Uint128,
     /// Account that created the node
@@ -54,16 +144,154 @@ pub struct Node {
     pub created_at: Timestamp,
     /// Block time when last edited by creator
     pub updated_at: Timestamp,
-    /// HTML title of the post
+    /// Block time before which the node is only visible to its creator
+    pub publish_at: Option<Timestamp>,
+    /// Block time at which the node expires and is no longer returned
+    pub expires_at: Option<Timestamp>,
+    /// Plain text title of the post
     pub title: String,
-    /// HTML body of the post
+    /// HTML body of the post, limited to whitelisted tags & attributes
     pub body: Option<String>,
+    /// Pointer to a body stored off chain, in place of an on-chain body
+    pub body_ref: Option<BodyRef>,
     /// URL links associated with the post
     pub links: Vec<Link>,
     /// Tags associated with the post
     pub tags: Vec<String>,
-    /// Not Safe For Work flag
-    pub nsfw: bool,
+    /// Content labels, such as NSFW
+    pub labels: Vec<Label>,
+    /// BCP-47 language tag of the title & body, if given
+    pub lang: Option<String>,
+    /// Who may reply to the node. If None, anyone may.
+    pub reply_policy: Option<ReplyPolicy>,
+    /// Who may reply to the node's direct replies, in addition to their own
+    /// policies
+    pub child_reply_policy: Option<ReplyPolicy>,
+    /// Kind of post, such as a question
+    pub kind: NodeKind,
+    /// ID of the reply accepted as the answer, if the node is a question
+    pub accepted_answer_id: Option<String>,
+    /// Author-provided translations of the title & body
+    pub translations: Option<Vec<Translation>>,
+    /// Node quoted by the post, if any
+    pub quote: Option<QuoteRef>,
+    /// Poll attached to the post, if any
+    pub poll: Option<Poll>,
+    /// Whether the viewer has liked the node, if a viewer was given
+    pub liked_by_viewer: Option<bool>,
+    /// Reactions the viewer has made to the node, if a viewer was given
+    pub viewer_reactions: Option<Vec<Reaction>>,
+    /// Whether the viewer created the node, if a viewer was given
+    pub is_author: Option<bool>,
+}
+
+#[cw_serde]
+pub enum NodeKind {
+    Post,
+    /// Question whose author can accept one direct reply as its answer
+    Question,
+}
+
+/// Running total of an address's tips in one token, either received or given
+#[cw_serde]
+pub struct TipTotal {
+    pub token: Token,
+    pub amount: Uint128,
+    pub n_tips: u32,
+}
+
+/// Reference to a node in this or another cw-post contract
+#[cw_serde]
+pub struct QuoteRef {
+    pub contract: Addr,
+    pub node_id: String,
+}
+
+/// Pointer to a node body stored off chain, such as on IPFS or Arweave,
+/// along with the hash by which it can be verified
+#[cw_serde]
+pub struct BodyRef {
+    pub uri: String,
+    /// Hex-encoded SHA-256 hash of the body
+    pub sha256: String,
+    /// Size of the body in bytes
+    pub size: u64,
+    pub mime: String,
+}
+
+/// Author-provided translation of a node's title & body
+#[cw_serde]
+pub struct Translation {
+    /// BCP-47 language tag of the translation
+    pub lang: String,
+    pub title: String,
+    pub body: Option<String>,
+}
+
+/// Who may reply to a node, set by its author. The author may always reply
+/// unless replies are closed. Set as a node's child reply policy, it applies
+/// to replies to each of the node's direct replies, with "author" & "mentioned"
+/// referring to the node itself, as for an AMA answered only by its author.
+#[cw_serde]
+pub enum ReplyPolicy {
+    Anyone,
+    AuthorOnly,
+    /// Only addresses mentioned by the node
+    MentionedOnly,
+    /// Only holders of at least this balance of the config token, inclusive,
+    /// so a min_balance of 1 admits any holder
+    TokenHolders {
+        min_balance: Uint128,
+    },
+    Closed,
+}
+
+/// Content label applied to a node by its author
+#[cw_serde]
+#[derive(Copy, Eq, PartialOrd, Ord)]
+pub enum Label {
+    Nsfw,
+    Spoiler,
+    Political,
+    Sponsored,
+}
+
+#[cw_serde]
+pub struct Poll {
+    /// Choices voters select from
+    pub options: Vec<String>,
+    /// Whether each voter may select several options
+    pub multiple_choice: bool,
+    /// Block time at which voting closes
+    pub closes_at: Timestamp,
+    /// How each vote is weighted
+    pub weighting: VoteWeighting,
+}
+
+#[cw_serde]
+pub enum VoteWeighting {
+    /// One vote per address
+    PerAddress,
+    /// Votes weighted by the voter's balance of the config token when voting.
+    /// Balances aren't snapshotted, so tokens moved to another address after
+    /// voting can be voted with again. Tallies are not sybil-resistant.
+    TokenBalance,
+}
+
+#[cw_serde]
+pub enum Reaction {
+    Image(String),
+    Emoji(String),
+}
+
+impl Reaction {
+    /// Key under which the reaction is indexed for a given node & account
+    pub fn to_key(&self) -> String {
+        match self {
+            Self::Image(url) => format!("image:{}", url),
+            Self::Emoji(emoji) => format!("emoji:{}", emoji),
+        }
+    }
 }
 
 #[cw_serde]
@@ -86,4 +314,18 @@ pub enum Link {
         label: Option<String>,
         provider: String,
     },
+    Nft {
+        contract: String,
+        token_id: String,
+    },
+    Contract {
+        address: String,
+    },
+    Tx {
+        hash: String,
+    },
+    Ipfs {
+        cid: String,
+        mime: Option<String>,
+    },
 }
@@ -1,7 +1,13 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Uint128, Uint64};
+use cosmwasm_std::{Addr, Binary, Timestamp, Uint128, Uint64};
 
-use crate::models::{Config, Link, Node};
+use crate::{
+    cursor::Cursor,
+    models::{
+        BodyRef, Config, Label, Link, Node, NodeKind, Poll, QuoteRef, Reaction, ReplyPolicy, TipTotal, Translation,
+    },
+    tokens::{Token, TokenAmount},
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -12,6 +18,7 @@ pub struct InstantiateMsg {
 
 #[cw_serde]
 #[derive(cw_orch::ExecuteFns)]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     Configure(Config),
     Reply(ReplyMsg),
@@ -19,6 +26,15 @@ pub enum ExecuteMsg {
     Like(LikeMsg),
     Tip(TipMsg),
     Delete(DeleteMsg),
+    Vote(VoteMsg),
+    Prune(PruneMsg),
+    Pin(PinMsg),
+    SetReplyPolicy(SetReplyPolicyMsg),
+    AcceptAnswer(AcceptAnswerMsg),
+    FundBounty(FundBountyMsg),
+    AwardBounty(AwardBountyMsg),
+    SettleBounty(SettleBountyMsg),
+    Claim(ClaimMsg),
 }
 
 #[cw_serde]
@@ -30,6 +46,7 @@ pub enum NodesQueryMsg {
 
 #[cw_serde]
 #[derive(cw_orch::QueryFns, QueryResponses)]
+#[allow(clippy::large_enum_variant)]
 pub enum QueryMsg {
     #[returns(InfoResponse)]
     Info {},
@@ -39,10 +56,32 @@ pub enum QueryMsg {
     Nodes(NodesQueryMsg),
     #[returns(ChatPaginationResponse)]
     Chat(ChatQueryArgs),
+    #[returns(NodesPaginationResponse)]
+    Mentions(MentionsQueryArgs),
+    #[returns(PollResultsResponse)]
+    PollResults(PollResultsQueryArgs),
+    #[returns(VerifyBodyResponse)]
+    VerifyBody(VerifyBodyQueryArgs),
+    #[returns(NodesPaginationResponse)]
+    Pinned(PinnedQueryArgs),
+    #[returns(BountyResponse)]
+    Bounty(BountyQueryArgs),
+    #[returns(ClaimableResponse)]
+    Claimable(ClaimableQueryArgs),
+    #[returns(UserStatsResponse)]
+    UserStats(UserStatsQueryArgs),
+    #[returns(LeaderboardResponse)]
+    TopEarners(LeaderboardQueryArgs),
+    #[returns(LeaderboardResponse)]
+    TopTippers(LeaderboardQueryArgs),
 }
 
 #[cw_serde]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// Max number of nodes to migrate. Migration resumes where it left off
+    /// each time the contract is migrated, until complete.
+    pub batch_size: Option<u64>,
+}
 
 #[cw_serde]
 pub struct DeleteMsg {
@@ -56,9 +95,78 @@ pub struct TipMsg {
 }
 
 #[cw_serde]
-pub enum ReactMsg {
-    Image(String),
-    Emoji(String),
+pub struct ClaimMsg {
+    pub token: Token,
+    /// Amount to withdraw. If None, the full claimable balance is withdrawn.
+    pub amount: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct FundBountyMsg {
+    pub node_id: String,
+    pub amount: Uint128,
+    /// Deadline for awarding the bounty, required when opening a new bounty
+    /// and omitted when adding to an open one
+    pub deadline: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct AwardBountyMsg {
+    pub node_id: String,
+    pub awards: Vec<BountyAward>,
+}
+
+#[cw_serde]
+pub struct BountyAward {
+    /// Direct reply to award
+    pub node_id: String,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct SettleBountyMsg {
+    pub node_id: String,
+}
+
+#[cw_serde]
+pub struct AcceptAnswerMsg {
+    pub question_id: String,
+    /// Direct reply to accept, replacing any accepted before. If None, the
+    /// accepted answer is cleared.
+    pub answer_id: Option<String>,
+}
+
+#[cw_serde]
+pub struct SetReplyPolicyMsg {
+    pub node_id: String,
+    pub reply_policy: ReplyPolicy,
+    /// Replaces the policy for replies to the node's direct replies. If None,
+    /// it's removed.
+    pub child_reply_policy: Option<ReplyPolicy>,
+}
+
+#[cw_serde]
+pub struct PinMsg {
+    pub node_id: String,
+}
+
+#[cw_serde]
+pub struct PruneMsg {
+    /// Max number of expired nodes to prune
+    pub limit: u16,
+}
+
+#[cw_serde]
+pub struct VoteMsg {
+    pub node_id: String,
+    /// Indices of the selected poll options
+    pub choices: Vec<u32>,
+}
+
+#[cw_serde]
+pub struct ReactMsg {
+    pub node_id: String,
+    pub reaction: Reaction,
 }
 
 pub type ReplyMsg = NodeInitArgs;
@@ -68,7 +176,61 @@ pub type LikeMsg = TipMsg;
 pub struct ChatQueryArgs {
     pub limit: u8,
     pub desc: Option<bool>,
-    pub cursor: Option<String>,
+    pub cursor: Option<Cursor>,
+    pub viewer: Option<String>,
+    pub fields: Option<Vec<NodeField>>,
+    pub include_labels: Option<Vec<Label>>,
+    pub exclude_labels: Option<Vec<Label>>,
+    pub lang: Option<String>,
+}
+
+#[cw_serde]
+pub struct MentionsQueryArgs {
+    pub address: String,
+    pub limit: u8,
+    pub desc: Option<bool>,
+    pub cursor: Option<Cursor>,
+    pub viewer: Option<String>,
+    pub fields: Option<Vec<NodeField>>,
+    pub include_labels: Option<Vec<Label>>,
+    pub exclude_labels: Option<Vec<Label>>,
+    pub lang: Option<String>,
+}
+
+#[cw_serde]
+pub struct VerifyBodyQueryArgs {
+    pub node_id: String,
+    /// Off-chain body to verify against the node's body reference
+    pub blob: Binary,
+}
+
+#[cw_serde]
+pub struct UserStatsQueryArgs {
+    pub address: String,
+}
+
+#[cw_serde]
+pub struct LeaderboardQueryArgs {
+    /// Token by which to rank addresses. Defaults to the config token.
+    pub token: Option<Token>,
+    pub limit: u8,
+    pub cursor: Option<Cursor>,
+}
+
+#[cw_serde]
+pub struct ClaimableQueryArgs {
+    pub address: String,
+}
+
+#[cw_serde]
+pub struct BountyQueryArgs {
+    pub node_id: String,
+}
+
+#[cw_serde]
+pub struct PollResultsQueryArgs {
+    pub node_id: String,
+    pub viewer: Option<String>,
 }
 
 #[cw_serde]
@@ -77,7 +239,22 @@ pub struct NodesByIdQueryArgs {
     pub order_by: OrderBy,
     pub limit: u8,
     pub desc: bool,
-    pub cursor: Option<Vec<String>>,
+    pub cursor: Option<Cursor>,
+    pub viewer: Option<String>,
+    pub fields: Option<Vec<NodeField>>,
+    pub include_labels: Option<Vec<Label>>,
+    pub exclude_labels: Option<Vec<Label>>,
+    pub lang: Option<String>,
+}
+
+#[cw_serde]
+pub struct PinnedQueryArgs {
+    pub parent_id: String,
+    pub viewer: Option<String>,
+    pub fields: Option<Vec<NodeField>>,
+    pub include_labels: Option<Vec<Label>>,
+    pub exclude_labels: Option<Vec<Label>>,
+    pub lang: Option<String>,
 }
 
 #[cw_serde]
@@ -86,7 +263,15 @@ pub struct NodesByParentIdQueryArgs {
     pub order_by: OrderBy,
     pub limit: u8,
     pub desc: bool,
-    pub cursor: Option<Vec<String>>,
+    pub cursor: Option<Cursor>,
+    /// List the parent's pinned children ahead of the first page, in addition
+    /// to `limit` others, and omit them from all pages
+    pub include_pinned_first: Option<bool>,
+    pub viewer: Option<String>,
+    pub fields: Option<Vec<NodeField>>,
+    pub include_labels: Option<Vec<Label>>,
+    pub exclude_labels: Option<Vec<Label>>,
+    pub lang: Option<String>,
 }
 
 #[cw_serde]
@@ -94,13 +279,32 @@ pub struct NodesByTagQueryArgs {
     pub tag: String,
     pub limit: u8,
     pub desc: bool,
-    pub cursor: Option<Vec<String>>,
+    pub cursor: Option<Cursor>,
+    pub viewer: Option<String>,
+    pub fields: Option<Vec<NodeField>>,
+    pub include_labels: Option<Vec<Label>>,
+    pub exclude_labels: Option<Vec<Label>>,
+    pub lang: Option<String>,
+}
+
+/// Optional node content fields a query can select. Nodes returned by a query
+/// with a fields selector omit the body, links and tags and truncate the title
+/// unless the corresponding field is selected.
+#[cw_serde]
+pub enum NodeField {
+    Title,
+    Body,
+    Links,
+    Tags,
 }
 
 #[cw_serde]
 pub enum OrderBy {
     Time,
     Likes,
+    /// The accepted answer, if any, ahead of the first page, followed by the
+    /// other replies in order of like count
+    AcceptedFirst,
 }
 
 #[cw_serde]
@@ -114,9 +318,22 @@ pub struct NodeInitArgs {
     pub parent_id: String,
     pub title: String,
     pub body: Option<String>,
+    pub body_ref: Option<BodyRef>,
     pub links: Option<Vec<Link>>,
     pub tags: Option<Vec<String>>,
-    pub nsfw: Option<bool>,
+    pub labels: Option<Vec<Label>>,
+    pub lang: Option<String>,
+    pub translations: Option<Vec<Translation>>,
+    pub reply_policy: Option<ReplyPolicy>,
+    /// Who may reply to the node's direct replies, in addition to their own
+    /// policies
+    pub child_reply_policy: Option<ReplyPolicy>,
+    pub kind: Option<NodeKind>,
+    pub quote: Option<QuoteRef>,
+    pub mentions: Option<Vec<String>>,
+    pub poll: Option<Poll>,
+    pub publish_at: Option<Timestamp>,
+    pub expires_at: Option<Timestamp>,
 }
 
 #[cw_serde]
@@ -124,13 +341,13 @@ pub struct ConfigResponse(pub Config);
 
 #[cw_serde]
 pub struct NodesPaginationResponse {
-    pub cursor: Option<Vec<String>>,
+    pub cursor: Option<Cursor>,
     pub nodes: Vec<Node>,
 }
 
 #[cw_serde]
 pub struct ChatPaginationResponse {
-    pub cursor: Option<String>,
+    pub cursor: Option<Cursor>,
     pub nodes: Vec<Node>,
 }
 
@@ -154,4 +371,81 @@ pub struct InfoResponse {
 pub struct CostResponse {
     pub total: Uint128,
     pub subtotals: NodeCostSubtotals,
+    /// Ways in which the node exceeds the configured content limits
+    pub violations: Vec<String>,
+}
+
+#[cw_serde]
+pub struct PollResultsResponse {
+    pub poll: Poll,
+    /// Total vote weight received by each option. With token balance
+    /// weighting, the same tokens may be counted more than once if moved
+    /// between voters.
+    pub tallies: Vec<Uint128>,
+    /// Number of addresses that have voted
+    pub n_voters: u32,
+    /// Whether voting has closed
+    pub is_closed: bool,
+    /// Options selected by the viewer, if a viewer was given and has voted
+    pub viewer_choices: Option<Vec<u32>>,
+}
+
+#[cw_serde]
+pub struct VerifyBodyResponse {
+    /// Whether the blob's hash & size match the node's body reference
+    pub is_valid: bool,
+    /// Hex-encoded SHA-256 hash of the blob
+    pub sha256: String,
+    /// Size of the blob in bytes
+    pub size: u64,
+}
+
+#[cw_serde]
+pub struct BountyResponse {
+    pub node_id: String,
+    /// Unawarded amount remaining in escrow
+    pub amount: Uint128,
+    /// Total amount contributed by funders
+    pub total_funded: Uint128,
+    pub deadline: Timestamp,
+    /// Whether the bounty can still be funded & awarded
+    pub is_open: bool,
+    pub funders: Vec<BountyFunder>,
+    /// Token in which the bounty is funded & paid out
+    pub token: Token,
+}
+
+#[cw_serde]
+pub struct BountyFunder {
+    pub address: Addr,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct ClaimableResponse {
+    /// Nonzero claimable balance of each token
+    pub balances: Vec<TokenAmount>,
+}
+
+#[cw_serde]
+pub struct UserStatsResponse {
+    pub address: Addr,
+    /// Tips received on the address's nodes, per token, not counting bounty
+    /// payouts
+    pub received: Vec<TipTotal>,
+    /// Tips sent by the address, per token
+    pub given: Vec<TipTotal>,
+}
+
+#[cw_serde]
+pub struct LeaderboardResponse {
+    pub cursor: Option<Cursor>,
+    /// Addresses in descending order of total tip amount
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[cw_serde]
+pub struct LeaderboardEntry {
+    pub address: Addr,
+    pub amount: Uint128,
 }
@@ -0,0 +1,37 @@
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::ContractError,
+    msg::{VerifyBodyQueryArgs, VerifyBodyResponse},
+    state::NODE_CONTENT,
+};
+
+use super::ReadonlyContext;
+
+/// Check a blob against the hash & size in a node's off-chain body reference
+pub fn query_verify_body(
+    ctx: ReadonlyContext,
+    params: VerifyBodyQueryArgs,
+) -> Result<VerifyBodyResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let VerifyBodyQueryArgs { node_id, blob } = params;
+
+    let body_ref = NODE_CONTENT
+        .may_load(deps.storage, &node_id)?
+        .and_then(|content| content.body_ref)
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} has no body reference", node_id),
+        })?;
+
+    let sha256 = Sha256::digest(blob.as_slice())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    let size = blob.len() as u64;
+
+    Ok(VerifyBodyResponse {
+        is_valid: sha256.eq_ignore_ascii_case(&body_ref.sha256) && size == body_ref.size,
+        sha256,
+        size,
+    })
+}
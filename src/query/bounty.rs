@@ -0,0 +1,45 @@
+use crate::{
+    error::ContractError,
+    msg::{BountyFunder, BountyQueryArgs, BountyResponse},
+    state::{Bounty, BOUNTIES, BOUNTY_FUNDS},
+};
+use cosmwasm_std::{Order, StdResult};
+
+use super::ReadonlyContext;
+
+/// Get a node's open bounty, along with its funders' contributions
+pub fn query_bounty(
+    ctx: ReadonlyContext,
+    params: BountyQueryArgs,
+) -> Result<BountyResponse, ContractError> {
+    let ReadonlyContext { deps, env } = ctx;
+    let BountyQueryArgs { node_id } = params;
+
+    let Bounty {
+        amount,
+        total_funded,
+        deadline,
+        token,
+        ..
+    } = BOUNTIES
+        .may_load(deps.storage, &node_id)?
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} has no open bounty", node_id),
+        })?;
+
+    let funders = BOUNTY_FUNDS
+        .prefix(&node_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|result| result.map(|(address, amount)| BountyFunder { address, amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(BountyResponse {
+        node_id,
+        amount,
+        total_funded,
+        is_open: env.block.time < deadline,
+        deadline,
+        funders,
+        token,
+    })
+}
@@ -0,0 +1,25 @@
+use crate::{
+    error::ContractError,
+    msg::{ClaimableQueryArgs, ClaimableResponse},
+    state::CLAIMABLE,
+};
+use cosmwasm_std::{Order, StdResult};
+
+use super::ReadonlyContext;
+
+/// Get an address's claimable balance of each token
+pub fn query_claimable(
+    ctx: ReadonlyContext,
+    params: ClaimableQueryArgs,
+) -> Result<ClaimableResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let address = deps.api.addr_validate(&params.address)?;
+
+    let balances = CLAIMABLE
+        .prefix(&address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|result| result.map(|(_, balance)| balance))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ClaimableResponse { balances })
+}
@@ -3,6 +3,7 @@ use crate::{
     fees::compute_node_cost,
     msg::{CostQueryArgs, CostResponse},
     state::CONFIG,
+    validation::find_node_violations,
 };
 
 use super::ReadonlyContext;
@@ -11,17 +12,30 @@ pub fn query_cost(
     ctx: ReadonlyContext,
     args: CostQueryArgs,
 ) -> Result<CostResponse, ContractError> {
-    let ReadonlyContext { deps, .. } = ctx;
+    let ReadonlyContext { deps, env } = ctx;
     let CostQueryArgs { is_update, node } = args;
     let config = CONFIG.load(deps.storage)?;
 
+    let violations = find_node_violations(deps, &env, &config, &node);
+
     let (total, subtotals) = compute_node_cost(
         &config.fees,
         is_update,
-        node.body.unwrap_or_default().len(),
+        node.body.unwrap_or_default().len()
+            + node
+                .translations
+                .unwrap_or_default()
+                .iter()
+                .map(|t| t.body.as_ref().map(|b| b.len()).unwrap_or_default())
+                .sum::<usize>(),
+        node.body_ref.is_some(),
         node.tags.unwrap_or_default().len(),
         node.links.unwrap_or_default().len(),
     )?;
 
-    Ok(CostResponse { total, subtotals })
+    Ok(CostResponse {
+        total,
+        subtotals,
+        violations,
+    })
 }
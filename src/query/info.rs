@@ -1,10 +1,10 @@
 use crate::{
     error::ContractError,
     msg::InfoResponse,
-    state::{CONFIG, COUNTERS, NODE_HEADER, NUM_NODES_COUNTER_KEY, OPERATOR, ROYALTIES},
+    state::{CONFIG, COUNTERS, NUM_NODES_COUNTER_KEY, OPERATOR, ROYALTIES},
 };
 
-use super::{nodes::build_node, ReadonlyContext};
+use super::ReadonlyContext;
 
 pub const PREVIEW_REPLY_COUNT: usize = 10;
 
@@ -1,6 +1,11 @@
+pub mod body;
+pub mod bounty;
+pub mod claimable;
 pub mod cost;
 pub mod info;
 pub mod nodes;
+pub mod poll;
+pub mod stats;
 
 use cosmwasm_std::{Deps, Env};
 
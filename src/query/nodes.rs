@@ -1,81 +1,89 @@
 use std::marker::PhantomData;
 
-use cosmwasm_std::{Order, StdResult, Storage, Uint64};
-use cw_storage_plus::Bound;
+use cosmwasm_std::{Addr, Api, Order, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Bound, PrimaryKey};
 
 use crate::{
+    cursor::{Cursor, CursorKey, CursorKind},
     error::ContractError,
-    models::Node,
+    math::add_u32,
+    models::{Label, Node, NodeKind},
     msg::{
-        ChatPaginationResponse, ChatQueryArgs, NodesByIdQueryArgs, NodesByParentIdQueryArgs, NodesByTagQueryArgs,
-        NodesPaginationResponse, OrderBy,
+        ChatPaginationResponse, ChatQueryArgs, MentionsQueryArgs, NodeField, NodesByIdQueryArgs,
+        NodesByParentIdQueryArgs, NodesByTagQueryArgs, NodesPaginationResponse, OrderBy, PinnedQueryArgs,
     },
     state::{
-        NodeAttributes, NodeHeader, COUNTERS, IX_PARENT_2_CHILD_ID, IX_PARENT_2_RANKED_CHILD_ID, IX_TAG_2_NODE_ID,
-        NODE_ATTRS, NODE_HEADER, NODE_NUM_REACTIONS, NODE_NUM_REPLIES, NODE_ROYALTIES, NODE_STATUS, NODE_TAGS,
-        NODE_UPDATED_AT, NUM_NODES_COUNTER_KEY,
+        NodeAttributes, NodeContent, NodeHeader, NodeStats, ACCEPTED_ANSWERS, COUNTERS, IX_ADDR_2_LIKED_ID,
+        IX_ADDR_2_MENTION_ID, IX_LANG_2_NODE_ID, IX_NODE_ADDR_2_REACTION, IX_PARENT_2_CHILD_ID,
+        IX_PARENT_2_RANKED_CHILD_ID, IX_PARENT_2_SCHEDULED_CHILD_ID, IX_TAG_2_NODE_ID, NODE_ATTRS, NODE_CONTENT,
+        NODE_HEADER, NODE_ID_COUNTER_KEY, NODE_STATS, PINS,
     },
+    validation::to_primary_language,
 };
 
 use super::ReadonlyContext;
 
 pub const MAX_LIMIT: u8 = 50;
 
-/// Query nodes by ID or by parent ID
+/// Max number of index keys scanned by a paginated query. A page may come
+/// back short, or empty, with a cursor to resume from.
+pub const MAX_SCANNED_KEYS: usize = 500;
+pub const TITLE_PREVIEW_LENGTH: usize = 80;
+
+/// Max number of published scheduled replies counted per built node. Any more
+/// are counted once Prune adds them to the stored reply count.
+pub const MAX_COUNTED_SCHEDULED_REPLIES: usize = 20;
+
+/// Query nodes by ID, in the order given or its reverse
 pub fn query_nodes_by_ids(
     ctx: ReadonlyContext,
     params: NodesByIdQueryArgs,
 ) -> Result<NodesPaginationResponse, ContractError> {
-    let ReadonlyContext { deps, .. } = ctx;
+    let ReadonlyContext { deps, env } = ctx;
     let limit = params.limit.min(MAX_LIMIT) as usize;
-    let ids = params.ids;
-    let mut nodes: Vec<Node> = Vec::with_capacity(limit);
-    let mut cursor_values: Box<Vec<String>> = Box::new(vec![]);
-
-    let next_cursor: Option<Vec<String>> = {
-        // Prepare args for Map range
-        let (min_bound, max_bound, order) = if params.desc {
-            let order = Order::Descending;
-            let max_bound: Option<Bound<&String>> = None;
-            let min_bound = params.cursor.and_then(|vals| {
-                *cursor_values = vals;
-                Some(Bound::Exclusive((&(*cursor_values)[0], PhantomData)))
-            });
-            (min_bound, max_bound, order)
-        } else {
-            let order = Order::Ascending;
-            let min_bound: Option<Bound<&String>> = None;
-            let max_bound = params.cursor.and_then(|vals| {
-                *cursor_values = vals;
-                Some(Bound::Exclusive((&(*cursor_values)[0], PhantomData)))
-            });
-            (min_bound, max_bound, order)
-        };
+    let options = NodeViewOptions::new(
+        deps.api,
+        env.block.time,
+        params.viewer.as_ref(),
+        params.fields.as_ref(),
+        params.include_labels.as_ref(),
+        params.exclude_labels.as_ref(),
+        params.lang.as_ref(),
+    )?;
+
+    let mut ids = params.ids;
+    if params.desc {
+        ids.reverse();
+    }
 
-        // Build return Nodes vec
-        for result in NODE_HEADER
-            .range(deps.storage, min_bound, max_bound, order)
-            .collect::<Vec<StdResult<_>>>()
-        {
-            if let Some(node) = build_node(deps.storage, result?.1)? {
-                nodes.push(node);
-            }
-            if nodes.len() == limit {
-                break;
-            }
-        }
+    // Skip past the provided ID's up to and including the cursor's
+    let start = match &params.cursor {
+        Some(cursor) => {
+            let cursor_id = cursor.decode_id(CursorKind::ByIds)?;
+            ids.iter()
+                .position(|id| *id == cursor_id)
+                .map(|i| i + 1)
+                .ok_or_else(|| ContractError::InvalidCursor {
+                    reason: "cursor ID not in provided ID's".to_owned(),
+                })?
+        },
+        None => 0,
+    };
 
-        // Get next cursor to return. This corresponds to a position in the
-        // provided ID's vec.
-        if let Some(u) = nodes.last() {
-            if *ids.last().unwrap() == u.id {
-                None
-            } else {
-                Some(vec![u.id.clone()])
-            }
-        } else {
-            None
-        }
+    // Build return Nodes vec
+    let (nodes, tail_id) = take_nodes(
+        deps.storage,
+        ids[start..].iter().map(|id| Ok(id.to_owned())),
+        |id: &String| id,
+        limit,
+        &options,
+    )?;
+
+    // Get next cursor to return. This corresponds to a position in the
+    // provided ID's vec.
+    let next_cursor = match tail_id {
+        Some(id) if ids.last() != Some(&id) => Some(Cursor::new(CursorKind::ByIds, CursorKey::Id(id))?),
+        _ => None,
     };
 
     Ok(NodesPaginationResponse {
@@ -88,212 +96,191 @@ pub fn query_nodes_by_parent_id(
     ctx: ReadonlyContext,
     params: NodesByParentIdQueryArgs,
 ) -> Result<NodesPaginationResponse, ContractError> {
-    let ReadonlyContext { deps, .. } = ctx;
+    let ReadonlyContext { deps, env } = ctx;
     let limit = params.limit.min(MAX_LIMIT) as usize;
     let parent_id = params.parent_id;
-    let mut nodes: Vec<Node> = Vec::with_capacity(limit);
-    let mut cursor_values: Box<Vec<String>> = Box::new(vec![]);
+    let options = NodeViewOptions::new(
+        deps.api,
+        env.block.time,
+        params.viewer.as_ref(),
+        params.fields.as_ref(),
+        params.include_labels.as_ref(),
+        params.exclude_labels.as_ref(),
+        params.lang.as_ref(),
+    )?;
+
+    // Pinned children are listed ahead of the first page & omitted from the
+    // ordered listing
+    let pinned_ids = if params.include_pinned_first.unwrap_or_default() {
+        PINS.may_load(deps.storage, &parent_id)?.unwrap_or_default()
+    } else {
+        vec![]
+    };
+
+    // The accepted answer is likewise listed ahead of the first page, after
+    // any pinned children
+    let accepted_id = match params.order_by {
+        OrderBy::AcceptedFirst => ACCEPTED_ANSWERS
+            .may_load(deps.storage, &parent_id)?
+            .filter(|id| !pinned_ids.contains(id)),
+        _ => None,
+    };
+    let head_ids: Vec<String> = pinned_ids.into_iter().chain(accepted_id).collect();
 
     // Build return Nodes vec
     // Return child nodes of the given parent ID
-    let next_cursor: Option<Vec<String>> = {
+    let (nodes, next_cursor) = {
         match params.order_by {
             // Return child nodes in order of creation time
             OrderBy::Time => {
-                // Prepare args for Map range
-                let (min_bound, max_bound, order) = if params.desc {
-                    let order = Order::Descending;
-                    let max_bound: Option<Bound<&String>> = None;
-                    let min_bound = params.cursor.and_then(|vals| {
-                        *cursor_values = vals;
-                        Some(Bound::Exclusive((&(*cursor_values)[0], PhantomData)))
-                    });
-                    (min_bound, max_bound, order)
-                } else {
-                    let order = Order::Ascending;
-                    let min_bound: Option<Bound<&String>> = None;
-                    let max_bound = params.cursor.and_then(|vals| {
-                        *cursor_values = vals;
-                        Some(Bound::Exclusive((&(*cursor_values)[0], PhantomData)))
-                    });
-                    (min_bound, max_bound, order)
+                let cursor_id = match &params.cursor {
+                    Some(cursor) => Some(cursor.decode_id(CursorKind::ByParentTime)?),
+                    None => None,
                 };
-
-                for result in IX_PARENT_2_CHILD_ID
-                    .prefix(&parent_id)
-                    .keys(deps.storage, min_bound, max_bound, order)
-                    .collect::<Vec<StdResult<_>>>()
-                {
-                    let child_id = result?;
-                    let header = NODE_HEADER.load(deps.storage, &child_id)?;
-                    if let Some(node) = build_node(deps.storage, header)? {
-                        nodes.push(node);
-                    }
-                    if nodes.len() == limit {
-                        break;
-                    }
-                }
+                let (min_bound, max_bound, order) = to_range_args(cursor_id.as_ref(), params.desc);
+
+                let (nodes, tail_id) = take_nodes(
+                    deps.storage,
+                    IX_PARENT_2_CHILD_ID
+                        .prefix(&parent_id)
+                        .keys(deps.storage, min_bound, max_bound, order)
+                        .filter(|result| !matches!(result, Ok(id) if head_ids.contains(id))),
+                    |child_id: &String| child_id,
+                    limit,
+                    &options,
+                )?;
 
                 // Get next cursor to return
-                if let Some(u) = nodes.last() {
-                    let ((_, final_child_id), _) = if params.desc {
-                        IX_PARENT_2_CHILD_ID.last(deps.storage)
-                    } else {
-                        IX_PARENT_2_CHILD_ID.first(deps.storage)
-                    }?
-                    .unwrap();
-
-                    if final_child_id == u.id {
-                        None
-                    } else {
-                        Some(vec![u.id.clone()])
-                    }
-                } else {
-                    None
-                }
+                let next_cursor = match tail_id {
+                    Some(id) => Some(Cursor::new(CursorKind::ByParentTime, CursorKey::Id(id))?),
+                    _ => None,
+                };
+
+                (nodes, next_cursor)
             },
             // Return child nodes in order of like count
-            OrderBy::Likes => {
-                // Prepare args for Map range
-                let (min_bound, max_bound, order) = if params.desc {
-                    let order = Order::Descending;
-                    let max_bound: Option<Bound<(&String, u32, &String)>> = None;
-                    let min_bound: Option<Bound<(&String, u32, &String)>> = params.cursor.and_then(|vals| {
-                        *cursor_values = vals;
-                        Some(Bound::Exclusive((
-                            (
-                                &parent_id,
-                                (*cursor_values)[0].parse::<u32>().unwrap(),
-                                &(*cursor_values)[1],
-                            ),
-                            PhantomData,
-                        )))
-                    });
-                    (min_bound, max_bound, order)
-                } else {
-                    let order = Order::Ascending;
-                    let min_bound: Option<Bound<(&String, u32, &String)>> = None;
-                    let max_bound: Option<Bound<(&String, u32, &String)>> = params.cursor.and_then(|vals| {
-                        *cursor_values = vals;
-                        Some(Bound::Exclusive((
-                            (
-                                &parent_id,
-                                (*cursor_values)[0].parse::<u32>().unwrap(),
-                                &(*cursor_values)[1],
-                            ),
-                            PhantomData,
-                        )))
-                    });
-                    (min_bound, max_bound, order)
+            OrderBy::Likes | OrderBy::AcceptedFirst => {
+                let cursor_key = match &params.cursor {
+                    Some(cursor) => Some(cursor.decode_ranked(CursorKind::ByParentLikes)?),
+                    None => None,
                 };
-
-                let mut tail_child_rank = 0u32;
-
-                for result in IX_PARENT_2_RANKED_CHILD_ID
-                    .keys(deps.storage, min_bound, max_bound, order)
-                    .collect::<Vec<StdResult<_>>>()
-                {
-                    let (_, child_rank, child_id) = result?;
-                    let header = NODE_HEADER.load(deps.storage, &child_id)?;
-
-                    if let Some(node) = build_node(deps.storage, header)? {
-                        nodes.push(node);
-                    }
-
-                    tail_child_rank = child_rank;
-
-                    if nodes.len() == limit {
-                        break;
-                    }
-                }
+                let (min_bound, max_bound, order) =
+                    to_range_args(cursor_key.as_ref().map(|(rank, id)| (*rank, id)), params.desc);
+
+                let (nodes, tail_key) = take_nodes(
+                    deps.storage,
+                    IX_PARENT_2_RANKED_CHILD_ID
+                        .sub_prefix(&parent_id)
+                        .keys(deps.storage, min_bound, max_bound, order)
+                        .filter(|result| !matches!(result, Ok((_, id)) if head_ids.contains(id))),
+                    |(_, child_id): &(u32, String)| child_id,
+                    limit,
+                    &options,
+                )?;
 
                 // Get next cursor to return
-                if let Some(u) = nodes.last() {
-                    let ((_, _, final_child_id), _) = if params.desc {
-                        IX_PARENT_2_RANKED_CHILD_ID.last(deps.storage)
-                    } else {
-                        IX_PARENT_2_RANKED_CHILD_ID.first(deps.storage)
-                    }?
-                    .unwrap();
-
-                    if final_child_id == u.id {
-                        None
-                    } else {
-                        Some(vec![tail_child_rank.to_string(), u.id.clone()])
-                    }
-                } else {
-                    None
-                }
+                let next_cursor = match tail_key {
+                    Some((rank, id)) => Some(Cursor::new(CursorKind::ByParentLikes, CursorKey::Ranked { rank, id })?),
+                    _ => None,
+                };
+
+                (nodes, next_cursor)
             },
         }
     };
 
+    let nodes = if params.cursor.is_none() && !head_ids.is_empty() {
+        let mut head_nodes = take_nodes_by_ids(deps.storage, &head_ids, &options)?;
+        head_nodes.extend(nodes);
+        head_nodes
+    } else {
+        nodes
+    };
+
     Ok(NodesPaginationResponse {
         cursor: next_cursor,
         nodes,
     })
 }
 
+/// Query the pinned children of a node, in the order pinned
+pub fn query_pinned(
+    ctx: ReadonlyContext,
+    params: PinnedQueryArgs,
+) -> Result<NodesPaginationResponse, ContractError> {
+    let ReadonlyContext { deps, env } = ctx;
+    let options = NodeViewOptions::new(
+        deps.api,
+        env.block.time,
+        params.viewer.as_ref(),
+        params.fields.as_ref(),
+        params.include_labels.as_ref(),
+        params.exclude_labels.as_ref(),
+        params.lang.as_ref(),
+    )?;
+    let pinned_ids = PINS.may_load(deps.storage, &params.parent_id)?.unwrap_or_default();
+
+    Ok(NodesPaginationResponse {
+        cursor: None,
+        nodes: take_nodes_by_ids(deps.storage, &pinned_ids, &options)?,
+    })
+}
+
+/// Build each visible node among the given node ID's, in order
+fn take_nodes_by_ids(
+    store: &dyn Storage,
+    node_ids: &[String],
+    options: &NodeViewOptions,
+) -> Result<Vec<Node>, ContractError> {
+    let (nodes, _) = take_nodes(
+        store,
+        node_ids.iter().map(|id| Ok(id.to_owned())),
+        |id: &String| id,
+        node_ids.len(),
+        options,
+    )?;
+    Ok(nodes)
+}
+
 pub fn query_nodes_by_tag(
     ctx: ReadonlyContext,
     params: NodesByTagQueryArgs,
 ) -> Result<NodesPaginationResponse, ContractError> {
-    let ReadonlyContext { deps, .. } = ctx;
+    let ReadonlyContext { deps, env } = ctx;
     let limit = params.limit.min(MAX_LIMIT) as usize;
-
-    // Prepare args for Map range. The cursor value is a node ID string
-    let mut cursor_values: Box<Vec<String>> = Box::new(vec![]);
-    let (min_bound, max_bound, order) = if params.desc {
-        let order = Order::Descending;
-        let max_bound: Option<Bound<(&String, u32, &String)>> = None;
-        let min_bound = params.cursor.and_then(|vals| {
-            *cursor_values = vals;
-            Some(Bound::Exclusive((
-                (&params.tag, cursor_values[0].parse::<u32>().unwrap(), &cursor_values[1]),
-                PhantomData,
-            )))
-        });
-        (min_bound, max_bound, order)
-    } else {
-        let order = Order::Ascending;
-        let min_bound: Option<Bound<(&String, u32, &String)>> = None;
-        let max_bound = params.cursor.and_then(|vals| {
-            *cursor_values = vals;
-            Some(Bound::Exclusive((
-                (&params.tag, cursor_values[0].parse::<u32>().unwrap(), &cursor_values[1]),
-                PhantomData,
-            )))
-        });
-        (min_bound, max_bound, order)
+    let tag = params.tag.to_lowercase();
+    let options = NodeViewOptions::new(
+        deps.api,
+        env.block.time,
+        params.viewer.as_ref(),
+        params.fields.as_ref(),
+        params.include_labels.as_ref(),
+        params.exclude_labels.as_ref(),
+        params.lang.as_ref(),
+    )?;
+
+    // Prepare args for Map range. The cursor key is a like count & node ID
+    let cursor_key = match &params.cursor {
+        Some(cursor) => Some(cursor.decode_ranked(CursorKind::ByTag)?),
+        None => None,
     };
+    let (min_bound, max_bound, order) = to_range_args(cursor_key.as_ref().map(|(rank, id)| (*rank, id)), params.desc);
 
     // Build return nodes
-    let mut nodes: Vec<Node> = Vec::with_capacity(limit);
-
-    for result in IX_TAG_2_NODE_ID
-        .keys(deps.storage, min_bound, max_bound, order)
-        .collect::<Vec<StdResult<_>>>()
-    {
-        let (_, _, node_id) = result?;
-
-        let header = NODE_HEADER.load(deps.storage, &node_id)?;
-        if let Some(node) = build_node(deps.storage, header)? {
-            nodes.push(node);
-        }
-        if nodes.len() == limit {
-            break;
-        }
-    }
+    let (nodes, tail_key) = take_nodes(
+        deps.storage,
+        IX_TAG_2_NODE_ID
+            .sub_prefix(&tag)
+            .keys(deps.storage, min_bound, max_bound, order),
+        |(_, node_id): &(u32, String)| node_id,
+        limit,
+        &options,
+    )?;
 
     // Get next cursor to return
-    let next_cursor = if let Some(u) = nodes.last() {
-        if nodes.len() == limit {
-            Some(vec![u.id.clone()])
-        } else {
-            None
-        }
-    } else {
-        None
+    let next_cursor = match tail_key {
+        Some((rank, id)) => Some(Cursor::new(CursorKind::ByTag, CursorKey::Ranked { rank, id })?),
+        _ => None,
     };
 
     Ok(NodesPaginationResponse {
@@ -306,99 +293,434 @@ pub fn query_chat(
     ctx: ReadonlyContext,
     params: ChatQueryArgs,
 ) -> Result<ChatPaginationResponse, ContractError> {
-    let ReadonlyContext { deps, .. } = ctx;
+    let ReadonlyContext { deps, env } = ctx;
     let limit = params.limit.min(MAX_LIMIT) as usize;
-    let root_id = "1".to_owned();
-    let n_total_nodes = COUNTERS.load(deps.storage, NUM_NODES_COUNTER_KEY)?.u64();
+    let options = NodeViewOptions::new(
+        deps.api,
+        env.block.time,
+        params.viewer.as_ref(),
+        params.fields.as_ref(),
+        params.include_labels.as_ref(),
+        params.exclude_labels.as_ref(),
+        params.lang.as_ref(),
+    )?;
+    let max_id = COUNTERS
+        .may_load(deps.storage, NODE_ID_COUNTER_KEY)?
+        .unwrap_or_default()
+        .u64();
+
+    // Node ID's are sequential, so the cursor is the last returned ID
+    let cursor_id = match &params.cursor {
+        Some(cursor) => {
+            Some(
+                cursor
+                    .decode_id(CursorKind::Chat)?
+                    .parse::<u64>()
+                    .map_err(|_| ContractError::InvalidCursor {
+                        reason: "expected numeric node ID".to_owned(),
+                    })?,
+            )
+        },
+        None => None,
+    };
 
-    let mut nodes: Vec<Node> = Vec::with_capacity(limit);
+    let desc = params.desc.unwrap_or_default();
+
+    // When filtering by language, only visit nodes indexed under its primary
+    // language subtag. Otherwise, visit every node ID in order.
+    let ids: Box<dyn Iterator<Item = StdResult<u64>>> = match &options.lang {
+        Some(lang) => {
+            let (min_bound, max_bound, order) = to_range_args(cursor_id, desc);
+            Box::new(IX_LANG_2_NODE_ID.prefix(&to_primary_language(lang)).keys(
+                deps.storage,
+                min_bound,
+                max_bound,
+                order,
+            ))
+        },
+        None if desc => Box::new((1..cursor_id.unwrap_or(max_id + 1)).rev().map(Ok)),
+        None => Box::new((cursor_id.unwrap_or_default() + 1..=max_id).map(Ok)),
+    };
 
-    let next_cursor: Option<String> = {
-        // Prepare args for Map range
-        if params.desc.unwrap_or_default() {
-            let start_id = params.cursor.unwrap_or("1".to_owned()).parse::<u64>().unwrap();
-            for id in start_id..=n_total_nodes {
-                let header = NODE_HEADER.load(deps.storage, &id.to_string())?;
-                if let Some(node) = build_node(deps.storage, header)? {
-                    nodes.push(node);
-                }
-                if nodes.len() == limit {
-                    break;
-                }
-            }
-        } else {
-            let start_id = params
-                .cursor
-                .unwrap_or(n_total_nodes.to_string())
-                .parse::<u64>()
-                .unwrap();
-            for id in (1..start_id).rev() {
-                let header = NODE_HEADER.load(deps.storage, &id.to_string())?;
-                if let Some(node) = build_node(deps.storage, header)? {
-                    nodes.push(node);
-                }
-                if nodes.len() == limit {
-                    break;
+    let (nodes, tail_id) = take_nodes(
+        deps.storage,
+        ids.map(|result| result.map(|id| id.to_string())),
+        |id: &String| id,
+        limit,
+        &options,
+    )?;
+
+    // Get next cursor to return
+    let next_cursor = match tail_id {
+        Some(id) => Some(Cursor::new(CursorKind::Chat, CursorKey::Id(id))?),
+        None => None,
+    };
+
+    Ok(ChatPaginationResponse {
+        cursor: next_cursor,
+        nodes,
+    })
+}
+
+/// Query nodes mentioning the given address, in order of creation
+pub fn query_mentions(
+    ctx: ReadonlyContext,
+    params: MentionsQueryArgs,
+) -> Result<NodesPaginationResponse, ContractError> {
+    let ReadonlyContext { deps, env } = ctx;
+    let limit = params.limit.min(MAX_LIMIT) as usize;
+    let address = deps.api.addr_validate(&params.address)?;
+    let options = NodeViewOptions::new(
+        deps.api,
+        env.block.time,
+        params.viewer.as_ref(),
+        params.fields.as_ref(),
+        params.include_labels.as_ref(),
+        params.exclude_labels.as_ref(),
+        params.lang.as_ref(),
+    )?;
+
+    // Mentions are indexed by numeric node ID, so the cursor is the last
+    // returned ID
+    let cursor_id =
+        match &params.cursor {
+            Some(cursor) => Some(cursor.decode_id(CursorKind::Mentions)?.parse::<u64>().map_err(|_| {
+                ContractError::InvalidCursor {
+                    reason: "expected numeric node ID".to_owned(),
                 }
-            }
+            })?),
+            None => None,
         };
+    let (min_bound, max_bound, order) = to_range_args(cursor_id, params.desc.unwrap_or_default());
+
+    let (nodes, tail_id) = take_nodes(
+        deps.storage,
+        IX_ADDR_2_MENTION_ID
+            .prefix(&address)
+            .keys(deps.storage, min_bound, max_bound, order)
+            .map(|result| result.map(|id| id.to_string())),
+        |id: &String| id,
+        limit,
+        &options,
+    )?;
 
-        // Get next cursor to return. This corresponds to a position in the
-        // provided ID's vec.
-        if let Some(u) = nodes.last() {
-            if nodes.len() < limit {
-                None
-            } else {
-                Some(u.id.clone())
-            }
-        } else {
-            None
-        }
+    // Get next cursor to return
+    let next_cursor = match tail_id {
+        Some(id) => Some(Cursor::new(CursorKind::Mentions, CursorKey::Id(id))?),
+        None => None,
     };
 
-    Ok(ChatPaginationResponse {
+    Ok(NodesPaginationResponse {
         cursor: next_cursor,
         nodes,
     })
 }
 
+/// Build up to `limit` nodes from a lazy iterator over index keys, scanning
+/// at most MAX_SCANNED_KEYS keys, so cost scales with the limit rather than
+/// the index size, even when most keys are filtered out. Returns the nodes
+/// along with the last scanned key to resume from if the page filled or the
+/// scan limit was hit, or None if the keys ran out.
+fn take_nodes<K>(
+    store: &dyn Storage,
+    keys: impl Iterator<Item = StdResult<K>>,
+    to_node_id: fn(&K) -> &String,
+    limit: usize,
+    options: &NodeViewOptions,
+) -> Result<(Vec<Node>, Option<K>), ContractError> {
+    let mut nodes: Vec<Node> = Vec::with_capacity(limit);
+
+    if limit == 0 {
+        return Ok((nodes, None));
+    }
+
+    for (i, result) in keys.enumerate() {
+        let key = result?;
+        if let Some(header) = NODE_HEADER.may_load(store, to_node_id(&key))? {
+            if let Some(node) = build_node(store, header, options)? {
+                nodes.push(node);
+            }
+        }
+        if nodes.len() == limit || i + 1 == MAX_SCANNED_KEYS {
+            return Ok((nodes, Some(key)));
+        }
+    }
+
+    Ok((nodes, None))
+}
+
+/// Build Map range bounds & order for resuming iteration after a cursor key
+fn to_range_args<'a, K: PrimaryKey<'a>>(
+    cursor_key: Option<K>,
+    desc: bool,
+) -> (Option<Bound<'a, K>>, Option<Bound<'a, K>>, Order) {
+    let bound = cursor_key.map(|k| Bound::Exclusive((k, PhantomData)));
+    if desc {
+        (None, bound, Order::Descending)
+    } else {
+        (bound, None, Order::Ascending)
+    }
+}
+
+/// Count up to MAX_COUNTED_SCHEDULED_REPLIES of the node's scheduled replies
+/// whose publish time has passed but that publish_scheduled_nodes hasn't yet
+/// added to its stored reply count
+fn count_published_replies(
+    store: &dyn Storage,
+    node_id: &String,
+    time: Timestamp,
+) -> Result<u32, ContractError> {
+    let empty_id = String::new();
+    let max_bound = Bound::exclusive((time.nanos().saturating_add(1), &empty_id));
+    IX_PARENT_2_SCHEDULED_CHILD_ID
+        .sub_prefix(node_id)
+        .keys(store, None, Some(max_bound), Order::Ascending)
+        .take(MAX_COUNTED_SCHEDULED_REPLIES)
+        .try_fold(0, |n, result| -> Result<u32, ContractError> {
+            result?;
+            add_u32(n, 1)
+        })
+}
+
+/// Per-query options that determine what each built Node contains
+pub struct NodeViewOptions {
+    /// Block time, before which scheduled nodes are hidden
+    pub time: Timestamp,
+    /// Account relative to which viewer-specific fields are computed
+    pub viewer: Option<Addr>,
+    /// Optional content fields to include. If None, all fields are included.
+    pub fields: Option<Vec<NodeField>>,
+    /// If given, only nodes with at least one of these labels are returned
+    pub include_labels: Option<Vec<Label>>,
+    /// Nodes with any of these labels are skipped
+    pub exclude_labels: Option<Vec<Label>>,
+    /// If given, only nodes in this language or one of its more specific
+    /// forms, like "en-US" for "en", are returned
+    pub lang: Option<String>,
+}
+
+impl NodeViewOptions {
+    pub fn new(
+        api: &dyn Api,
+        time: Timestamp,
+        viewer: Option<&String>,
+        fields: Option<&Vec<NodeField>>,
+        include_labels: Option<&Vec<Label>>,
+        exclude_labels: Option<&Vec<Label>>,
+        lang: Option<&String>,
+    ) -> Result<Self, ContractError> {
+        Ok(Self {
+            time,
+            viewer: match viewer {
+                Some(viewer) => Some(api.addr_validate(viewer)?),
+                None => None,
+            },
+            fields: fields.cloned(),
+            include_labels: include_labels.cloned(),
+            exclude_labels: exclude_labels.cloned(),
+            lang: lang.cloned(),
+        })
+    }
+
+    /// Does the node's language match the language filter, if any?
+    pub fn matches_lang(
+        &self,
+        lang: Option<&String>,
+    ) -> bool {
+        match (&self.lang, lang) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(filter), Some(lang)) => {
+                let lang = lang.to_ascii_lowercase();
+                let filter = filter.to_ascii_lowercase();
+                lang == filter || lang.starts_with(&format!("{}-", filter))
+            },
+        }
+    }
+
+    /// Do the node's labels pass the include & exclude filters?
+    pub fn matches_labels(
+        &self,
+        labels: &[Label],
+    ) -> bool {
+        let is_included = self
+            .include_labels
+            .as_ref()
+            .map(|include| include.iter().any(|l| labels.contains(l)))
+            .unwrap_or(true);
+        let is_excluded = self
+            .exclude_labels
+            .as_ref()
+            .map(|exclude| exclude.iter().any(|l| labels.contains(l)))
+            .unwrap_or(false);
+        is_included && !is_excluded
+    }
+
+    pub fn includes(
+        &self,
+        field: NodeField,
+    ) -> bool {
+        self.fields
+            .as_ref()
+            .map(|fields| fields.contains(&field))
+            .unwrap_or(true)
+    }
+}
+
+/// Truncate a title to at most TITLE_PREVIEW_LENGTH chars
+fn to_title_preview(title: String) -> String {
+    match title.char_indices().nth(TITLE_PREVIEW_LENGTH) {
+        Some((i, _)) => title[..i].to_owned(),
+        None => title,
+    }
+}
+
 pub fn build_node(
     store: &dyn Storage,
     node_header: NodeHeader,
+    options: &NodeViewOptions,
 ) -> Result<Option<Node>, ContractError> {
     let NodeHeader {
         id,
         parent_id,
+        depth,
         created_by,
+        created_at,
+        publish_at,
+        expires_at,
     } = node_header;
-    let status = NODE_STATUS.load(store, &id)?;
-    let updated_at = NODE_UPDATED_AT.may_load(store, &id)?.unwrap_or_default();
-    let n_replies = NODE_NUM_REPLIES.may_load(store, &id)?.unwrap_or_default();
-    let n_reactions = NODE_NUM_REACTIONS.may_load(store, &id)?.unwrap_or_default();
-    let royalties = NODE_ROYALTIES.may_load(store, &id)?.unwrap_or_default();
-    let tags = NODE_TAGS.load(store, &id)?;
+
+    // Treat expired nodes as gone, even before they're pruned
+    if expires_at.map(|t| t <= options.time).unwrap_or_default() {
+        return Ok(None);
+    }
+
+    // Hide scheduled nodes until published, except from their author
+    if let Some(publish_at) = publish_at {
+        if publish_at > options.time && options.viewer.as_ref() != Some(&created_by) {
+            return Ok(None);
+        }
+    }
+
     let NodeAttributes {
-        created_at,
         title,
-        body,
-        links,
-        nsfw,
+        tags,
+        labels,
+        lang,
+        reply_policy,
+        child_reply_policy,
+        kind,
+        quote,
+        poll,
     } = NODE_ATTRS.load(store, &id)?;
 
+    // Skip nodes filtered out by label or language
+    if !options.matches_labels(&labels) || !options.matches_lang(lang.as_ref()) {
+        return Ok(None);
+    }
+
+    let NodeStats {
+        status,
+        updated_at,
+        n_replies,
+        n_reactions,
+        royalties,
+    } = NODE_STATS.load(store, &id)?;
+
+    // Count published scheduled replies not yet included in the stored reply
+    // count
+    let n_replies = add_u32(n_replies, count_published_replies(store, &id, options.time)?)?;
+
+    // Omit content fields not selected by the query, only loading heavy
+    // content if selected.
+    let title = if options.includes(NodeField::Title) {
+        title
+    } else {
+        to_title_preview(title)
+    };
+    let tags = if options.includes(NodeField::Tags) {
+        tags
+    } else {
+        vec![]
+    };
+    let (body, body_ref, translations, links) =
+        if options.includes(NodeField::Body) || options.includes(NodeField::Links) {
+            let NodeContent {
+                body,
+                body_ref,
+                links,
+                translations,
+            } = NODE_CONTENT.load(store, &id)?;
+            let (body, body_ref, translations) = if options.includes(NodeField::Body) {
+                (body, body_ref, translations)
+            } else {
+                (None, None, None)
+            };
+            (
+                body,
+                body_ref,
+                translations,
+                if options.includes(NodeField::Links) {
+                    links
+                } else {
+                    vec![]
+                },
+            )
+        } else {
+            (None, None, None, vec![])
+        };
+
+    let kind = kind.unwrap_or(NodeKind::Post);
+    let accepted_answer_id = match kind {
+        NodeKind::Question => ACCEPTED_ANSWERS.may_load(store, &id)?,
+        NodeKind::Post => None,
+    };
+
+    // Compute fields relative to the viewer's account, if any
+    let (liked_by_viewer, viewer_reactions, is_author) = if let Some(viewer) = &options.viewer {
+        let reactions = IX_NODE_ADDR_2_REACTION
+            .prefix((&id, viewer))
+            .range(store, None, None, Order::Ascending)
+            .map(|result| result.map(|(_, reaction)| reaction))
+            .collect::<StdResult<Vec<_>>>()?;
+        (
+            Some(IX_ADDR_2_LIKED_ID.has(store, (viewer, &id))),
+            Some(reactions),
+            Some(*viewer == created_by),
+        )
+    } else {
+        (None, None, None)
+    };
+
     Ok(Some(Node {
         id,
         status,
         parent_id,
+        depth,
         created_by,
         created_at,
         updated_at,
+        publish_at,
+        expires_at,
         n_replies,
         n_reactions,
         royalties,
         title,
         body,
+        body_ref,
         links,
         tags,
-        nsfw,
+        labels,
+        lang,
+        reply_policy,
+        child_reply_policy,
+        kind,
+        accepted_answer_id,
+        translations,
+        quote,
+        poll,
+        liked_by_viewer,
+        viewer_reactions,
+        is_author,
     }))
 }
@@ -0,0 +1,42 @@
+use crate::{
+    error::ContractError,
+    msg::{PollResultsQueryArgs, PollResultsResponse},
+    state::{PollTally, NODE_ATTRS, POLL_TALLIES, POLL_VOTES},
+};
+
+use super::ReadonlyContext;
+
+pub fn query_poll_results(
+    ctx: ReadonlyContext,
+    params: PollResultsQueryArgs,
+) -> Result<PollResultsResponse, ContractError> {
+    let ReadonlyContext { deps, env } = ctx;
+    let PollResultsQueryArgs { node_id, viewer } = params;
+
+    let poll = NODE_ATTRS
+        .may_load(deps.storage, &node_id)?
+        .and_then(|attrs| attrs.poll)
+        .ok_or_else(|| ContractError::ValidationError {
+            reason: format!("node {} has no poll", node_id),
+        })?;
+
+    let PollTally { n_voters, weights } = POLL_TALLIES.load(deps.storage, &node_id)?;
+
+    let viewer_choices = match viewer {
+        Some(viewer) => {
+            let viewer = deps.api.addr_validate(&viewer)?;
+            POLL_VOTES
+                .may_load(deps.storage, (&node_id, &viewer))?
+                .map(|vote| vote.choices)
+        },
+        None => None,
+    };
+
+    Ok(PollResultsResponse {
+        is_closed: env.block.time >= poll.closes_at,
+        poll,
+        tallies: weights,
+        n_voters,
+        viewer_choices,
+    })
+}
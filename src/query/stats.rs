@@ -0,0 +1,101 @@
+use std::marker::PhantomData;
+
+use crate::{
+    cursor::{Cursor, CursorKey, CursorKind},
+    error::ContractError,
+    models::TipTotal,
+    msg::{LeaderboardEntry, LeaderboardQueryArgs, LeaderboardResponse, UserStatsQueryArgs, UserStatsResponse},
+    state::{CONFIG, IX_TOKEN_2_RANKED_EARNER, IX_TOKEN_2_RANKED_TIPPER, TIPS_GIVEN, TIPS_RECEIVED},
+};
+use cosmwasm_std::{Addr, Order, StdResult, Uint128};
+use cw_storage_plus::{Bound, Map};
+
+use super::{nodes::MAX_LIMIT, ReadonlyContext};
+
+/// Get an address's tip totals received & given, per token
+pub fn query_user_stats(
+    ctx: ReadonlyContext,
+    params: UserStatsQueryArgs,
+) -> Result<UserStatsResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let address = deps.api.addr_validate(&params.address)?;
+
+    let load_totals = |totals: Map<(&Addr, &String), TipTotal>| {
+        totals
+            .prefix(&address)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|result| result.map(|(_, total)| total))
+            .collect::<StdResult<Vec<_>>>()
+    };
+
+    Ok(UserStatsResponse {
+        received: load_totals(TIPS_RECEIVED)?,
+        given: load_totals(TIPS_GIVEN)?,
+        address,
+    })
+}
+
+/// Paginate over the addresses that have received the most in tips
+pub fn query_top_earners(
+    ctx: ReadonlyContext,
+    params: LeaderboardQueryArgs,
+) -> Result<LeaderboardResponse, ContractError> {
+    query_leaderboard(ctx, params, IX_TOKEN_2_RANKED_EARNER, CursorKind::TopEarners)
+}
+
+/// Paginate over the addresses that have given the most in tips
+pub fn query_top_tippers(
+    ctx: ReadonlyContext,
+    params: LeaderboardQueryArgs,
+) -> Result<LeaderboardResponse, ContractError> {
+    query_leaderboard(ctx, params, IX_TOKEN_2_RANKED_TIPPER, CursorKind::TopTippers)
+}
+
+fn query_leaderboard(
+    ctx: ReadonlyContext,
+    params: LeaderboardQueryArgs,
+    ranked_index: Map<(&String, u128, &Addr), u8>,
+    cursor_kind: CursorKind,
+) -> Result<LeaderboardResponse, ContractError> {
+    let ReadonlyContext { deps, .. } = ctx;
+    let limit = params.limit.min(MAX_LIMIT) as usize;
+    let token_key = match params.token {
+        Some(token) => token.to_key(),
+        None => CONFIG.load(deps.storage)?.token.to_key(),
+    };
+
+    // Resume below the cursor's amount & address
+    let cursor_key = match &params.cursor {
+        Some(cursor) => Some(cursor.decode_amount(cursor_kind.to_owned())?),
+        None => None,
+    };
+    let max_bound = cursor_key
+        .as_ref()
+        .map(|(amount, address)| Bound::Exclusive(((amount.u128(), address), PhantomData)));
+
+    let entries = ranked_index
+        .sub_prefix(&token_key)
+        .keys(deps.storage, None, max_bound, Order::Descending)
+        .take(limit)
+        .map(|result| {
+            result.map(|(amount, address)| LeaderboardEntry {
+                address,
+                amount: Uint128::from(amount),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // Get next cursor to return
+    let cursor = match entries.last() {
+        Some(entry) if entries.len() == limit => Some(Cursor::new(
+            cursor_kind,
+            CursorKey::Amount {
+                amount: entry.amount,
+                address: entry.address.to_owned(),
+            },
+        )?),
+        _ => None,
+    };
+
+    Ok(LeaderboardResponse { cursor, entries })
+}
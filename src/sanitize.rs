@@ -0,0 +1,511 @@
+use crate::{
+    models::SanitizeMode,
+    msg::NodeInitArgs,
+    validation::{parse_url, DEFAULT_URL_SCHEMES},
+};
+
+/// Tags allowed in node bodies
+pub const ALLOWED_BODY_TAGS: [&str; 16] = [
+    "a",
+    "b",
+    "blockquote",
+    "br",
+    "code",
+    "em",
+    "hr",
+    "i",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "s",
+    "strong",
+    "u",
+    "ul",
+];
+
+/// Tags whose content is removed along with the tags themselves when
+/// stripping, rather than kept as text
+const DROPPED_CONTENT_TAGS: [&str; 2] = ["script", "style"];
+
+/// Is the attribute allowed on the given body tag?
+fn is_allowed_attr(
+    tag: &str,
+    attr: &str,
+) -> bool {
+    matches!((tag, attr), ("a", "href") | ("a", "title"))
+}
+
+enum Markup {
+    Comment,
+    Tag(Tag),
+}
+
+struct Tag {
+    name: String,
+    is_closing: bool,
+    is_self_closing: bool,
+    attrs: Vec<(String, Option<String>)>,
+}
+
+impl Tag {
+    fn to_html(&self) -> String {
+        if self.is_closing {
+            return format!("</{}>", self.name);
+        }
+        let mut html = format!("<{}", self.name);
+        for (name, value) in self.attrs.iter() {
+            match value {
+                Some(value) => html.push_str(&format!(" {}=\"{}\"", name, value.replace('"', "&quot;"))),
+                None => html.push_str(&format!(" {}", name)),
+            }
+        }
+        if self.is_self_closing {
+            html.push_str(" /");
+        }
+        html.push('>');
+        html
+    }
+}
+
+/// Describe the disallowed markup in the node's titles & bodies, including
+/// translations, if any
+pub fn find_markup_violations(args: &NodeInitArgs) -> Vec<String> {
+    let mut violations: Vec<String> = vec![];
+    if let Err(reason) = sanitize_title(&args.title, &SanitizeMode::Reject) {
+        violations.push(reason);
+    }
+    if let Some(Err(reason)) = args
+        .body
+        .as_ref()
+        .map(|body| sanitize_body(body, &SanitizeMode::Reject))
+    {
+        violations.push(reason);
+    }
+    for translation in args.translations.as_ref().unwrap_or(&vec![]).iter() {
+        if let Err(reason) = sanitize_title(&translation.title, &SanitizeMode::Reject) {
+            violations.push(format!("{} translation {}", translation.lang, reason));
+        }
+        if let Some(Err(reason)) = translation
+            .body
+            .as_ref()
+            .map(|body| sanitize_body(body, &SanitizeMode::Reject))
+        {
+            violations.push(format!("{} translation {}", translation.lang, reason));
+        }
+    }
+    violations
+}
+
+/// Remove disallowed markup from the node's titles & bodies, including
+/// translations
+pub fn strip_markup(args: &mut NodeInitArgs) -> Result<(), String> {
+    args.title = sanitize_title(&args.title, &SanitizeMode::Strip)?;
+    if let Some(body) = &args.body {
+        args.body = Some(sanitize_body(body, &SanitizeMode::Strip)?);
+    }
+    for translation in args.translations.iter_mut().flatten() {
+        translation.title = sanitize_title(&translation.title, &SanitizeMode::Strip)?;
+        if let Some(body) = &translation.body {
+            translation.body = Some(sanitize_body(body, &SanitizeMode::Strip)?);
+        }
+    }
+    Ok(())
+}
+
+/// Sanitize a node title as plain text. In Reject mode, returns a
+/// description of the first markup found. In Strip mode, returns the title
+/// with markup removed.
+pub fn sanitize_title(
+    title: &str,
+    mode: &SanitizeMode,
+) -> Result<String, String> {
+    sanitize_html(title, false, mode).map_err(|reason| format!("title: {}", reason))
+}
+
+/// Sanitize a node body, allowing only whitelisted tags & attributes. In
+/// Reject mode, returns a description of the first violation found. In Strip
+/// mode, returns the body with disallowed markup removed.
+pub fn sanitize_body(
+    body: &str,
+    mode: &SanitizeMode,
+) -> Result<String, String> {
+    sanitize_html(body, true, mode).map_err(|reason| format!("body: {}", reason))
+}
+
+fn sanitize_html(
+    html: &str,
+    allow_tags: bool,
+    mode: &SanitizeMode,
+) -> Result<String, String> {
+    let is_strip = *mode == SanitizeMode::Strip;
+    let mut output = String::with_capacity(html.len());
+    let mut dropped_tag: Option<String> = None;
+    let mut rest = html;
+
+    while let Some(i) = rest.find('<') {
+        let (text, tail) = rest.split_at(i);
+
+        if dropped_tag.is_none() {
+            output.push_str(text);
+        }
+
+        let (markup, n) = match parse_markup(tail) {
+            Some(parsed) => parsed,
+            None => {
+                // Not well-formed markup, so escape it as text
+                if !is_strip {
+                    return Err("unescaped '<' character".to_owned());
+                }
+                if dropped_tag.is_none() {
+                    output.push_str("&lt;");
+                }
+                rest = &tail[1..];
+                continue;
+            },
+        };
+
+        rest = &tail[n..];
+
+        let mut tag = match markup {
+            Markup::Comment if is_strip => continue,
+            Markup::Comment => return Err("comments are not allowed".to_owned()),
+            Markup::Tag(tag) => tag,
+        };
+
+        // Skip everything up to the end of a dropped script or style element
+        if let Some(name) = &dropped_tag {
+            if tag.is_closing && tag.name == *name {
+                dropped_tag = None;
+            }
+            continue;
+        }
+
+        if !allow_tags || !ALLOWED_BODY_TAGS.contains(&tag.name.as_str()) {
+            if !is_strip {
+                return Err(format!("<{}> tags are not allowed", tag.name));
+            }
+            if DROPPED_CONTENT_TAGS.contains(&tag.name.as_str()) && !tag.is_closing && !tag.is_self_closing {
+                dropped_tag = Some(tag.name);
+            }
+            continue;
+        }
+
+        let mut attrs = Vec::with_capacity(tag.attrs.len());
+
+        for (name, value) in tag.attrs.into_iter() {
+            let reason = if !is_allowed_attr(&tag.name, &name) {
+                Some(format!("{} attribute is not allowed on <{}> tags", name, tag.name))
+            } else if name == "href" && !value.as_deref().map(is_safe_href).unwrap_or_default() {
+                Some(format!("unsafe href on <{}> tag", tag.name))
+            } else {
+                None
+            };
+            match reason {
+                Some(reason) if !is_strip => return Err(reason),
+                Some(_) => {},
+                None => attrs.push((name, value)),
+            }
+        }
+
+        tag.attrs = attrs;
+        output.push_str(&tag.to_html());
+    }
+
+    if dropped_tag.is_none() {
+        output.push_str(rest);
+    }
+
+    Ok(output)
+}
+
+/// Is the href an absolute URL with an allowed scheme and without character
+/// references that could hide its real scheme from this check?
+fn is_safe_href(href: &str) -> bool {
+    let has_char_ref = href.match_indices('&').any(|(i, _)| {
+        let tail = &href[i + 1..];
+        match tail.find(';') {
+            Some(j) => {
+                let name = &tail[..j];
+                name != "amp" && !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '#')
+            },
+            None => false,
+        }
+    });
+    !has_char_ref
+        && parse_url(href)
+            .map(|(scheme, _)| DEFAULT_URL_SCHEMES.contains(&scheme.as_str()))
+            .unwrap_or_default()
+}
+
+/// Parse a comment or tag at the start of the string, returning it along
+/// with the number of bytes it spans, or None if it isn't well-formed.
+fn parse_markup(s: &str) -> Option<(Markup, usize)> {
+    let bytes = s.as_bytes();
+
+    if let Some(comment) = s.strip_prefix("<!--") {
+        return comment.find("-->").map(|i| (Markup::Comment, 4 + i + 3));
+    }
+
+    let mut i = 1;
+    let is_closing = bytes.get(i) == Some(&b'/');
+    if is_closing {
+        i += 1;
+    }
+
+    let name_start = i;
+    while i < bytes.len() && bytes[i].is_ascii_alphanumeric() {
+        i += 1;
+    }
+    if i == name_start {
+        return None;
+    }
+
+    let name = s[name_start..i].to_ascii_lowercase();
+    let mut attrs: Vec<(String, Option<String>)> = vec![];
+    let mut is_self_closing = false;
+
+    loop {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        match bytes.get(i)? {
+            b'>' => {
+                i += 1;
+                break;
+            },
+            b'/' if bytes.get(i + 1) == Some(&b'>') => {
+                is_self_closing = true;
+                i += 2;
+                break;
+            },
+            _ => {},
+        }
+
+        // Parse attribute name
+        let attr_start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !b"=>/\"'<".contains(&bytes[i]) {
+            i += 1;
+        }
+        if i == attr_start {
+            return None;
+        }
+        let attr_name = s[attr_start..i].to_ascii_lowercase();
+
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+
+        // Parse optional attribute value, quoted or unquoted
+        let value = if bytes.get(i) == Some(&b'=') {
+            i += 1;
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            match bytes.get(i)? {
+                quote @ (b'"' | b'\'') => {
+                    let value_start = i + 1;
+                    let value_len = s[value_start..].find(*quote as char)?;
+                    i = value_start + value_len + 1;
+                    Some(s[value_start..value_start + value_len].to_owned())
+                },
+                _ => {
+                    let value_start = i;
+                    while i < bytes.len() && !bytes[i].is_ascii_whitespace() && !b">\"'<=`".contains(&bytes[i]) {
+                        i += 1;
+                    }
+                    Some(s[value_start..i].to_owned())
+                },
+            }
+        } else {
+            None
+        };
+
+        attrs.push((attr_name, value));
+    }
+
+    if is_closing && !attrs.is_empty() {
+        return None;
+    }
+
+    Some((
+        Markup::Tag(Tag {
+            name,
+            is_closing,
+            is_self_closing,
+            attrs,
+        }),
+        i,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip_body(body: &str) -> String {
+        sanitize_body(body, &SanitizeMode::Strip).unwrap()
+    }
+
+    fn reject_body(body: &str) -> Result<String, String> {
+        sanitize_body(body, &SanitizeMode::Reject)
+    }
+
+    #[test]
+    fn allows_whitelisted_markup() {
+        let body = "<p>Hi <b>there</b>, see <a href=\"https://example.com/?a=1&amp;b=2\" title=\"x\">this</a><br/></p>";
+        assert_eq!(
+            reject_body(body).unwrap(),
+            "<p>Hi <b>there</b>, see <a href=\"https://example.com/?a=1&amp;b=2\" title=\"x\">this</a><br /></p>"
+        );
+    }
+
+    #[test]
+    fn rejects_javascript_hrefs() {
+        for body in [
+            "<a href=\"javascript:alert(1)\">x</a>",
+            "<a href=\"JavaScript:alert(1)\">x</a>",
+            "<a HREF='javascript:alert(1)'>x</a>",
+            "<a href=\"data:text/html,<script>alert(1)</script>\">x</a>",
+            "<a href=\"java\tscript:alert(1)\">x</a>",
+            "<a href=\" javascript:alert(1)\">x</a>",
+        ] {
+            assert!(reject_body(body).is_err(), "{}", body);
+            assert_eq!(strip_body(body), "<a>x</a>", "{}", body);
+        }
+    }
+
+    #[test]
+    fn rejects_entity_encoded_hrefs() {
+        for body in [
+            "<a href=\"jav&#x61;script:alert(1)\">x</a>",
+            "<a href=\"&#106;avascript:alert(1)\">x</a>",
+            "<a href=\"&#106avascript:alert(1)\">x</a>",
+            "<a href=\"javascript&colon;alert(1)\">x</a>",
+            "<a href=\"java&Tab;script:alert(1)\">x</a>",
+            "<a href=\"https&#58;//example.com\">x</a>",
+        ] {
+            assert!(reject_body(body).is_err(), "{}", body);
+            assert_eq!(strip_body(body), "<a>x</a>", "{}", body);
+        }
+    }
+
+    #[test]
+    fn escapes_slash_separated_attributes() {
+        let body = "<a/onclick=alert(1)>x</a>";
+        assert!(reject_body(body).is_err());
+        let stripped = strip_body(body);
+        assert!(stripped.starts_with("&lt;a/onclick"), "{}", stripped);
+        assert!(!stripped.contains("<a/"), "{}", stripped);
+    }
+
+    #[test]
+    fn drops_event_handler_attributes() {
+        for body in [
+            "<a href=\"https://example.com\" onclick=\"alert(1)\">x</a>",
+            "<a href=\"https://example.com\" onclick=alert(1)>x</a>",
+            "<a href=\"https://example.com\" ONCLICK='alert(1)'>x</a>",
+        ] {
+            assert!(reject_body(body).is_err(), "{}", body);
+            assert_eq!(strip_body(body), "<a href=\"https://example.com\">x</a>", "{}", body);
+        }
+    }
+
+    #[test]
+    fn handles_unquoted_and_backtick_attributes() {
+        let body = "<a href=javascript:alert(1)>x</a>";
+        assert!(reject_body(body).is_err());
+        assert_eq!(strip_body(body), "<a>x</a>");
+
+        let body = "<a href=https://example.com>x</a>";
+        assert_eq!(reject_body(body).unwrap(), "<a href=\"https://example.com\">x</a>");
+
+        let body = "<a href=`javascript:alert(1)`>x</a>";
+        assert!(reject_body(body).is_err());
+        assert_eq!(strip_body(body), "<a>x</a>");
+
+        let body = "<a title=`x` onmouseover=alert(1)>x</a>";
+        assert!(reject_body(body).is_err());
+        let stripped = strip_body(body);
+        assert!(!stripped.contains("onmouseover"), "{}", stripped);
+        assert!(!stripped.contains('`'), "{}", stripped);
+    }
+
+    #[test]
+    fn quotes_attribute_values() {
+        let body = "<a title='say \"hi\"' href=\"https://example.com\">x</a>";
+        assert_eq!(
+            reject_body(body).unwrap(),
+            "<a title=\"say &quot;hi&quot;\" href=\"https://example.com\">x</a>"
+        );
+    }
+
+    #[test]
+    fn handles_comments() {
+        assert!(reject_body("a<!-- x -->b").is_err());
+        assert_eq!(strip_body("a<!-- <script>alert(1)</script> -->b"), "ab");
+
+        // Unterminated comments are escaped as text
+        assert!(reject_body("a<!-- <script>alert(1)</script>").is_err());
+        let stripped = strip_body("a<!-- <b>x");
+        assert_eq!(stripped, "a&lt;!-- <b>x");
+        let stripped = strip_body("a<!--> <img src=x onerror=alert(1)>");
+        assert_eq!(stripped, "a&lt;!--> ");
+    }
+
+    #[test]
+    fn drops_script_and_style_content() {
+        assert_eq!(strip_body("a<script>alert(1)</script>b"), "ab");
+        assert_eq!(strip_body("a<STYLE>p { color: red }</style>b"), "ab");
+        assert_eq!(strip_body("a<script>alert(1)"), "a");
+        assert!(reject_body("<script>alert(1)</script>").is_err());
+    }
+
+    #[test]
+    fn strips_nested_script_tags() {
+        let stripped = strip_body("<scr<script>ipt>alert(1)</script>");
+        assert_eq!(stripped, "&lt;scr");
+
+        let stripped = strip_body("<scr<script></script>ipt>alert(1)</scr<script></script>ipt>");
+        assert!(!stripped.contains("<script"), "{}", stripped);
+        assert!(!stripped.contains("</script"), "{}", stripped);
+    }
+
+    #[test]
+    fn strips_disallowed_tags_keeping_text() {
+        assert_eq!(strip_body("<div><img src=x onerror=alert(1)>hi</div>"), "hi");
+        assert_eq!(strip_body("<iframe src=\"https://example.com\"></iframe>x"), "x");
+        assert!(reject_body("<img src=x>").is_err());
+    }
+
+    #[test]
+    fn escapes_stray_angle_brackets() {
+        assert!(reject_body("1 < 2").is_err());
+        assert_eq!(strip_body("1 < 2 > 0"), "1 &lt; 2 > 0");
+        assert_eq!(strip_body("a <"), "a &lt;");
+    }
+
+    #[test]
+    fn sanitizes_titles_as_plain_text() {
+        assert_eq!(
+            sanitize_title("Plain title", &SanitizeMode::Reject).unwrap(),
+            "Plain title"
+        );
+        assert!(sanitize_title("<b>Bold</b> title", &SanitizeMode::Reject)
+            .unwrap_err()
+            .starts_with("title:"));
+        assert_eq!(
+            sanitize_title("<b>Bold</b> title", &SanitizeMode::Strip).unwrap(),
+            "Bold title"
+        );
+        assert_eq!(
+            sanitize_title("<a href=\"https://example.com\">link</a>", &SanitizeMode::Strip).unwrap(),
+            "link"
+        );
+        assert_eq!(
+            sanitize_title("x<script>alert(1)</script>y", &SanitizeMode::Strip).unwrap(),
+            "xy"
+        );
+        assert_eq!(sanitize_title("1 < 2", &SanitizeMode::Strip).unwrap(), "1 &lt; 2");
+    }
+}
@@ -1,17 +1,27 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Response, Storage, Timestamp, Uint128, Uint64};
+use cosmwasm_std::{
+    attr, Addr, Api, Attribute, DepsMut, Env, Order, QuerierWrapper, Response, StdError, StdResult, Storage, Timestamp,
+    Uint128, Uint64,
+};
 
 use crate::{
     error::ContractError,
     execute::Context,
-    math::add_u64,
-    models::{Link, NodeStatus},
+    math::{add_u32, add_u64, sub_u32, sub_u64},
+    models::{
+        BodyRef, Label, Link, NodeKind, NodeStatus, Poll, QuoteRef, Reaction, ReplyPolicy, SanitizeMode, TipTotal,
+        Translation,
+    },
     msg::{InstantiateMsg, NodeInitArgs},
+    sanitize::strip_markup,
+    tokens::{Token, TokenAmount},
+    validation::{to_primary_language, validate_node},
 };
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Bound, Item, Map};
 
 use super::models::Config;
 
+pub const ROOT_NODE_ID: &str = "1";
 pub const NODE_ID_COUNTER_KEY: &str = "node_id";
 pub const NUM_NODES_COUNTER_KEY: &str = "num_nodes";
 
@@ -23,35 +33,127 @@ pub const ROYALTIES: Item<Uint128> = Item::new("royalties");
 pub const COUNTERS: Map<&str, Uint64> = Map::new("counters");
 
 pub const NODE_HEADER: Map<&String, NodeHeader> = Map::new("nh");
-pub const NODE_STATUS: Map<&String, NodeStatus> = Map::new("node_status");
-pub const NODE_ATTRS: Map<&String, NodeAttributes> = Map::new("node_attrs");
-pub const NODE_TAGS: Map<&String, Vec<String>> = Map::new("node_tags");
-pub const NODE_UPDATED_AT: Map<&String, Timestamp> = Map::new("node_t_updated");
-pub const NODE_NUM_REPLIES: Map<&String, u16> = Map::new("n_replies");
-pub const NODE_NUM_REACTIONS: Map<&String, u16> = Map::new("n_reacts");
+pub const NODE_STATS: Map<&String, NodeStats> = Map::new("ns");
+pub const NODE_ATTRS: Map<&String, NodeAttributes> = Map::new("na");
+pub const NODE_CONTENT: Map<&String, NodeContent> = Map::new("nc");
 pub const NODE_NUM_LIKES: Map<&String, u32> = Map::new("n_likes");
-pub const NODE_ROYALTIES: Map<&String, Uint128> = Map::new("node_royalties");
 
 pub const IX_PARENT_2_CHILD_ID: Map<(&String, &String), u8> = Map::new("npc");
 pub const IX_PARENT_2_RANKED_CHILD_ID: Map<(&String, u32, &String), u8> = Map::new("nrpc");
+pub const IX_PARENT_2_SCHEDULED_CHILD_ID: Map<(&String, u64, &String), u8> = Map::new("nspc");
+pub const IX_PUBLISH_2_NODE_ID: Map<(u64, &String), u8> = Map::new("pnid");
+pub const IX_EXPIRY_2_NODE_ID: Map<(u64, &String), u8> = Map::new("enid");
 pub const IX_ADDR_2_LIKED_ID: Map<(&Addr, &String), u8> = Map::new("ali");
 pub const IX_LIKED_ID_2_ADDR: Map<(&String, &Addr), u8> = Map::new("lai");
 pub const IX_TAG_2_NODE_ID: Map<(&String, u32, &String), u8> = Map::new("tni");
+pub const IX_LANG_2_NODE_ID: Map<(&String, u64), u8> = Map::new("lni");
+pub const IX_NODE_ADDR_2_REACTION: Map<(&String, &Addr, &String), Reaction> = Map::new("nar");
+pub const IX_ADDR_2_MENTION_ID: Map<(&Addr, u64), u8> = Map::new("amn");
+pub const NODE_MENTIONS: Map<&String, Vec<Addr>> = Map::new("nm");
+pub const PINS: Map<&String, Vec<String>> = Map::new("pins");
+pub const ACCEPTED_ANSWERS: Map<&String, String> = Map::new("aa");
+
+/// Max number of published scheduled replies counted toward their parents on
+/// each node creation
+pub const PUBLISH_BATCH_SIZE: usize = 10;
+
+/// Max number of likes, reactions & poll votes removed with a node per call
+pub const REMOVE_NODE_BATCH_SIZE: usize = 100;
+
+/// Max number of pinned children per parent when not configured
+pub const DEFAULT_MAX_PINS: u32 = 3;
+
+pub const TIPS_RECEIVED: Map<(&Addr, &String), TipTotal> = Map::new("trcv");
+pub const TIPS_GIVEN: Map<(&Addr, &String), TipTotal> = Map::new("tgvn");
+pub const IX_TOKEN_2_RANKED_EARNER: Map<(&String, u128, &Addr), u8> = Map::new("tre");
+pub const IX_TOKEN_2_RANKED_TIPPER: Map<(&String, u128, &Addr), u8> = Map::new("trt");
+pub const CLAIMABLE: Map<(&Addr, &String), TokenAmount> = Map::new("clm");
+pub const BOUNTIES: Map<&String, Bounty> = Map::new("bty");
+pub const BOUNTY_FUNDS: Map<(&String, &Addr), Uint128> = Map::new("btf");
+pub const PRUNE_POOLS: Map<&String, TokenAmount> = Map::new("ppl");
+pub const PRUNE_FUNDED_IDS: Map<&String, Token> = Map::new("pfi");
 
+/// Max number of distinct funders per bounty
+pub const MAX_BOUNTY_FUNDERS: u32 = 50;
+
+/// Max number of replies a bounty is split among by likes
+pub const MAX_BOUNTY_SPLIT: usize = 10;
+
+pub const POLL_TALLIES: Map<&String, PollTally> = Map::new("pt");
+pub const POLL_VOTES: Map<(&String, &Addr), PollVote> = Map::new("pv");
+
+/// Node data that never changes after creation
 #[cw_serde]
 pub struct NodeHeader {
     pub id: String,
     pub parent_id: String,
+    pub depth: u32,
     pub created_by: Addr,
+    pub created_at: Timestamp,
+    pub publish_at: Option<Timestamp>,
+    pub expires_at: Option<Timestamp>,
 }
 
+/// Hot node data, updated by replies, reactions, tips, etc.
+#[cw_serde]
+pub struct NodeStats {
+    pub status: NodeStatus,
+    pub updated_at: Timestamp,
+    pub n_replies: u32,
+    pub n_reactions: u32,
+    pub royalties: Uint128,
+}
+
+/// Lightweight node content, returned by every listing
 #[cw_serde]
 pub struct NodeAttributes {
-    pub created_at: Timestamp,
     pub title: String,
+    pub tags: Vec<String>,
+    pub labels: Vec<Label>,
+    pub lang: Option<String>,
+    pub reply_policy: Option<ReplyPolicy>,
+    pub child_reply_policy: Option<ReplyPolicy>,
+    pub kind: Option<NodeKind>,
+    pub quote: Option<QuoteRef>,
+    pub poll: Option<Poll>,
+}
+
+/// Heavy node content, only loaded when selected by a query
+#[cw_serde]
+pub struct NodeContent {
     pub body: Option<String>,
+    pub body_ref: Option<BodyRef>,
     pub links: Vec<Link>,
-    pub nsfw: bool,
+    pub translations: Option<Vec<Translation>>,
+}
+
+/// Funds held in escrow for the best replies to a node
+#[cw_serde]
+pub struct Bounty {
+    /// Unawarded amount remaining in escrow
+    pub amount: Uint128,
+    /// Total amount contributed by funders
+    pub total_funded: Uint128,
+    /// Block time after which the bounty can no longer be awarded
+    pub deadline: Timestamp,
+    pub n_funders: u32,
+    /// Token in which the bounty is funded & paid out, being the config token
+    /// when opened
+    pub token: Token,
+}
+
+/// Running totals of a node's poll votes
+#[cw_serde]
+pub struct PollTally {
+    pub n_voters: u32,
+    pub weights: Vec<Uint128>,
+}
+
+/// A single address's vote in a node's poll
+#[cw_serde]
+pub struct PollVote {
+    pub choices: Vec<u32>,
+    pub weight: Uint128,
 }
 
 /// Top-level initialization of contract state
@@ -82,17 +184,94 @@ pub fn init(
     root.parent_id = String::from("");
 
     // Create post's root node in the reply tree
-    init_node(deps.storage, &info.sender, env.block.time, root)?;
+    let (_, node_attrs) = init_node(deps, &env, &info.sender, root)?;
 
-    Ok(Response::new().add_attribute("action", "instantiate"))
+    Ok(Response::new()
+        .add_attribute("action", "instantiate")
+        .add_attributes(node_attrs))
 }
 
+/// Create a node from the given args, returning its ID & event attributes
+/// with its ID and each address it mentions
 pub fn init_node(
-    store: &mut dyn Storage,
+    deps: DepsMut,
+    env: &Env,
     sender: &Addr,
-    time: Timestamp,
     args: NodeInitArgs,
-) -> Result<(), ContractError> {
+) -> Result<(String, Vec<Attribute>), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut args = args;
+    if config.sanitize_mode == Some(SanitizeMode::Strip) {
+        strip_markup(&mut args).map_err(|reason| ContractError::ValidationError { reason })?;
+    }
+
+    validate_node(deps.as_ref(), env, &config, &args)?;
+
+    let mentions = to_mentioned_addrs(deps.api, &args)?;
+    let store = deps.storage;
+    let time = env.block.time;
+
+    // Count a few published scheduled replies toward their parents' reply
+    // counts, so queries needn't
+    let mut attrs = publish_scheduled_nodes(store, time, PUBLISH_BATCH_SIZE)?;
+
+    // A publish time that has already passed means publish immediately
+    let publish_at = args.publish_at.filter(|t| *t > time);
+
+    // Ensure the parent exists & accepts replies. Only the root node, created
+    // on instantiation, has no parent.
+    let (depth, parent_stats) = if args.parent_id.is_empty() && !NODE_HEADER.has(store, &ROOT_NODE_ID.to_owned()) {
+        (0, None)
+    } else {
+        let parent = NODE_HEADER
+            .may_load(store, &args.parent_id)?
+            .ok_or_else(|| ContractError::ValidationError {
+                reason: format!("parent node {} not found", args.parent_id),
+            })?;
+        if parent.expires_at.map(|t| t <= time).unwrap_or_default() {
+            return Err(ContractError::ValidationError {
+                reason: format!("parent node {} has expired", args.parent_id),
+            });
+        }
+        ensure_published(&parent, time)?;
+        let parent_stats = NODE_STATS.load(store, &args.parent_id)?;
+        let parent_attrs = NODE_ATTRS.load(store, &args.parent_id)?;
+        ensure_can_reply(store, deps.querier, &config, &parent, &parent_attrs, sender)?;
+        match parent_stats.status {
+            NodeStatus::Normal => {},
+            NodeStatus::Deleted => {
+                return Err(ContractError::ValidationError {
+                    reason: format!("parent node {} is deleted", args.parent_id),
+                })
+            },
+            NodeStatus::Locked => {
+                return Err(ContractError::ValidationError {
+                    reason: format!("parent node {} is locked", args.parent_id),
+                })
+            },
+        }
+        let depth = add_u32(parent.depth, 1)?;
+        if let Some(max_depth) = config.max_depth {
+            if depth > max_depth {
+                return Err(ContractError::ValidationError {
+                    reason: format!("max reply depth of {} exceeded", max_depth),
+                });
+            }
+        }
+        (depth, Some(parent_stats))
+    };
+
+    // Replies expire at the earlier of their own expiry time & the config's
+    // TTL, if any. The root node never expires.
+    let expires_at = match parent_stats {
+        Some(_) => [args.expires_at, config.node_ttl.map(|ttl| time.plus_seconds(ttl))]
+            .into_iter()
+            .flatten()
+            .min(),
+        None => None,
+    };
+
     // Tick up total count of nodes in tree
     increment_counter(store, NUM_NODES_COUNTER_KEY, 1u64)?.to_string();
 
@@ -110,31 +289,90 @@ pub fn init_node(
         &NodeHeader {
             id: node_id.to_owned(),
             parent_id: args.parent_id.to_owned(),
+            depth,
             created_by: sender.to_owned(),
+            created_at: time,
+            publish_at,
+            expires_at,
         },
     )?;
 
+    // Index expiring nodes by expiry time for pruning
+    if let Some(expires_at) = expires_at {
+        IX_EXPIRY_2_NODE_ID.save(store, (expires_at.nanos(), &node_id), &0)?;
+    }
+
     // Save data that changes on specific executions
-    NODE_UPDATED_AT.save(store, &node_id, &time)?;
-    NODE_STATUS.save(store, &node_id, &NodeStatus::Normal)?;
-    NODE_NUM_REPLIES.update(store, &args.parent_id, |n| -> Result<_, ContractError> {
-        Ok(n.unwrap_or_default() + 1)
-    })?;
+    NODE_STATS.save(
+        store,
+        &node_id,
+        &NodeStats {
+            status: NodeStatus::Normal,
+            updated_at: time,
+            n_replies: 0,
+            n_reactions: 0,
+            royalties: Uint128::zero(),
+        },
+    )?;
+
+    // Increment the parent's reply count. The root node has no parent.
+    // Scheduled replies are instead indexed by publish time, counted by
+    // queries once published until added to the count by
+    // publish_scheduled_nodes.
+    if let Some(mut parent_stats) = parent_stats {
+        if let Some(publish_at) = publish_at {
+            IX_PARENT_2_SCHEDULED_CHILD_ID.save(store, (&args.parent_id, publish_at.nanos(), &node_id), &0)?;
+            IX_PUBLISH_2_NODE_ID.save(store, (publish_at.nanos(), &node_id), &0)?;
+        } else {
+            parent_stats.n_replies = add_u32(parent_stats.n_replies, 1)?;
+            NODE_STATS.save(store, &args.parent_id, &parent_stats)?;
+        }
+    }
 
     // Save node data that only changes on user edits
-    let node_data = NodeAttributes {
-        links: args.links.unwrap_or_default(),
-        created_at: time,
-        title: args.title,
-        body: args.body,
-        nsfw: args.nsfw.unwrap_or_default(),
-    };
+    let tags = args.tags.unwrap_or_default();
+    let mut labels = args.labels.unwrap_or_default();
+    labels.sort();
+    labels.dedup();
 
-    NODE_ATTRS.save(store, &node_id, &node_data)?;
+    NODE_ATTRS.save(
+        store,
+        &node_id,
+        &NodeAttributes {
+            title: args.title,
+            tags: tags.to_owned(),
+            labels,
+            lang: args.lang.to_owned(),
+            reply_policy: args.reply_policy,
+            child_reply_policy: args.child_reply_policy,
+            kind: args.kind.to_owned(),
+            quote: args.quote,
+            poll: args.poll.to_owned(),
+        },
+    )?;
 
-    let tags = args.tags.unwrap_or_default();
+    // Start the node's poll with no votes
+    if let Some(poll) = &args.poll {
+        POLL_TALLIES.save(
+            store,
+            &node_id,
+            &PollTally {
+                n_voters: 0,
+                weights: vec![Uint128::zero(); poll.options.len()],
+            },
+        )?;
+    }
 
-    NODE_TAGS.save(store, &node_id, &tags)?;
+    NODE_CONTENT.save(
+        store,
+        &node_id,
+        &NodeContent {
+            body: args.body,
+            body_ref: args.body_ref,
+            links: args.links.unwrap_or_default(),
+            translations: args.translations,
+        },
+    )?;
 
     // Insert entries in lookup table used for finding nodes by tag
     for tag in tags.iter() {
@@ -142,9 +380,291 @@ pub fn init_node(
         IX_TAG_2_NODE_ID.save(store, (&tag, 0, &node_id), &0)?;
     }
 
+    let node_num = node_id
+        .parse::<u64>()
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    // Insert entry in lookup table used for finding nodes by language
+    if let Some(lang) = &args.lang {
+        IX_LANG_2_NODE_ID.save(store, (&to_primary_language(lang), node_num), &0)?;
+    }
+
+    // Insert entries in lookup table used for finding mentions by address
+    for addr in mentions.iter() {
+        IX_ADDR_2_MENTION_ID.save(store, (addr, node_num), &0)?;
+    }
+    if !mentions.is_empty() {
+        NODE_MENTIONS.save(store, &node_id, &mentions)?;
+    }
+
+    // Mentions in scheduled nodes are announced once published
+    attrs.push(attr("node_id", &node_id));
+    if publish_at.is_none() {
+        attrs.extend(mentions.iter().map(|addr| attr("mention", addr)));
+    }
+
+    Ok((node_id, attrs))
+}
+
+/// Ensure the node's publish time, if any, has passed
+pub fn ensure_published(
+    header: &NodeHeader,
+    time: Timestamp,
+) -> Result<(), ContractError> {
+    if header.publish_at.map(|t| t > time).unwrap_or_default() {
+        return Err(ContractError::ValidationError {
+            reason: format!("node {} is not yet published", header.id),
+        });
+    }
     Ok(())
 }
 
+/// Ensure the node's expiry time, if any, hasn't been reached
+pub fn ensure_not_expired(
+    header: &NodeHeader,
+    time: Timestamp,
+) -> Result<(), ContractError> {
+    if header.expires_at.map(|t| t <= time).unwrap_or_default() {
+        return Err(ContractError::ValidationError {
+            reason: format!("node {} has expired", header.id),
+        });
+    }
+    Ok(())
+}
+
+/// Add up to `limit` of the earliest published scheduled replies to their
+/// parents' reply counts, removing them from the scheduled indexes. Returns
+/// event attributes with each published node's ID & the addresses it
+/// mentions.
+pub fn publish_scheduled_nodes(
+    store: &mut dyn Storage,
+    time: Timestamp,
+    limit: usize,
+) -> Result<Vec<Attribute>, ContractError> {
+    let empty_id = String::new();
+    let max_bound = Bound::exclusive((time.nanos().saturating_add(1), &empty_id));
+    let published = IX_PUBLISH_2_NODE_ID
+        .keys(store, None, Some(max_bound), Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(u64, String)>>>()?;
+
+    let mut attrs: Vec<Attribute> = Vec::with_capacity(published.len());
+
+    for (publish_at, node_id) in published.iter() {
+        IX_PUBLISH_2_NODE_ID.remove(store, (*publish_at, node_id));
+        let parent_id = match NODE_HEADER.may_load(store, node_id)? {
+            Some(header) => header.parent_id,
+            None => continue,
+        };
+        IX_PARENT_2_SCHEDULED_CHILD_ID.remove(store, (&parent_id, *publish_at, node_id));
+        if let Some(mut parent_stats) = NODE_STATS.may_load(store, &parent_id)? {
+            parent_stats.n_replies = add_u32(parent_stats.n_replies, 1)?;
+            NODE_STATS.save(store, &parent_id, &parent_stats)?;
+        }
+        attrs.push(attr("published_node_id", node_id));
+        for addr in NODE_MENTIONS.may_load(store, node_id)?.unwrap_or_default().iter() {
+            attrs.push(attr("mention", addr));
+        }
+    }
+
+    Ok(attrs)
+}
+
+/// Ensure the sender may reply to the parent node under its reply policy and
+/// under the child reply policy of the parent's own parent, if any
+fn ensure_can_reply(
+    store: &dyn Storage,
+    querier: QuerierWrapper,
+    config: &Config,
+    parent: &NodeHeader,
+    parent_attrs: &NodeAttributes,
+    sender: &Addr,
+) -> Result<(), ContractError> {
+    let policy = parent_attrs.reply_policy.to_owned().unwrap_or(ReplyPolicy::Anyone);
+    if !is_allowed_by_policy(store, querier, config, policy, parent, sender)? {
+        return Err(ContractError::NotAuthorized {
+            reason: format!("replies to node {} are restricted by its reply policy", parent.id),
+        });
+    }
+
+    let child_policy = NODE_ATTRS
+        .may_load(store, &parent.parent_id)?
+        .and_then(|attrs| attrs.child_reply_policy);
+    if let Some(policy) = child_policy {
+        let grandparent = NODE_HEADER.load(store, &parent.parent_id)?;
+        if !is_allowed_by_policy(store, querier, config, policy, &grandparent, sender)? {
+            return Err(ContractError::NotAuthorized {
+                reason: format!(
+                    "replies to node {} are restricted by the child reply policy of node {}",
+                    parent.id, grandparent.id
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Whether the policy allows the sender to reply, with the given node's
+/// author & mentions being those the policy refers to
+fn is_allowed_by_policy(
+    store: &dyn Storage,
+    querier: QuerierWrapper,
+    config: &Config,
+    policy: ReplyPolicy,
+    node: &NodeHeader,
+    sender: &Addr,
+) -> Result<bool, ContractError> {
+    let is_author = *sender == node.created_by;
+
+    Ok(match policy {
+        ReplyPolicy::Anyone => true,
+        ReplyPolicy::Closed => false,
+        _ if is_author => true,
+        ReplyPolicy::AuthorOnly => false,
+        ReplyPolicy::MentionedOnly => NODE_MENTIONS
+            .may_load(store, &node.id)?
+            .map(|mentions| mentions.contains(sender))
+            .unwrap_or_default(),
+        ReplyPolicy::TokenHolders { min_balance } => config.token.query_balance(querier, sender)? >= min_balance,
+    })
+}
+
+/// Delete all of a node's data & index entries, including its likes,
+/// reactions, poll votes, mentions, pins & accepted answers, and remove it
+/// from its parent's reply count. Removes at most `limit` likes, reactions &
+/// poll votes per call, returning false without removing the rest of the node
+/// until a later call finds none left. Does nothing if the node doesn't exist.
+pub fn remove_node(
+    store: &mut dyn Storage,
+    node_id: &String,
+    limit: usize,
+) -> Result<bool, ContractError> {
+    let NodeHeader {
+        parent_id, publish_at, ..
+    } = match NODE_HEADER.may_load(store, node_id)? {
+        Some(header) => header,
+        None => return Ok(true),
+    };
+    let NodeAttributes { tags, lang, poll, .. } = NODE_ATTRS.load(store, node_id)?;
+    let n_likes = NODE_NUM_LIKES.may_load(store, node_id)?.unwrap_or_default();
+
+    let likers = IX_LIKED_ID_2_ADDR
+        .prefix(node_id)
+        .keys(store, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    for addr in likers.iter() {
+        IX_LIKED_ID_2_ADDR.remove(store, (node_id, addr));
+        IX_ADDR_2_LIKED_ID.remove(store, (addr, node_id));
+    }
+    let limit = limit - likers.len();
+
+    let reactions = IX_NODE_ADDR_2_REACTION
+        .sub_prefix(node_id)
+        .keys(store, None, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<(Addr, String)>>>()?;
+    for (addr, key) in reactions.iter() {
+        IX_NODE_ADDR_2_REACTION.remove(store, (node_id, addr, key));
+    }
+    let limit = limit - reactions.len();
+
+    if poll.is_some() {
+        let voters = POLL_VOTES
+            .prefix(node_id)
+            .keys(store, None, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<Addr>>>()?;
+        for addr in voters.iter() {
+            POLL_VOTES.remove(store, (node_id, addr));
+        }
+        if voters.len() == limit {
+            return Ok(false);
+        }
+        POLL_TALLIES.remove(store, node_id);
+    } else if limit == 0 {
+        return Ok(false);
+    }
+
+    // Remove the node from its parent's replies. Scheduled replies not yet
+    // added to the parent's reply count are only counted via the scheduled
+    // index.
+    IX_PARENT_2_CHILD_ID.remove(store, (&parent_id, node_id));
+    IX_PARENT_2_RANKED_CHILD_ID.remove(store, (&parent_id, n_likes, node_id));
+    let scheduled_key = publish_at.map(|t| (&parent_id, t.nanos(), node_id));
+    if let Some(key) = scheduled_key.filter(|key| IX_PARENT_2_SCHEDULED_CHILD_ID.has(store, *key)) {
+        IX_PARENT_2_SCHEDULED_CHILD_ID.remove(store, key);
+        IX_PUBLISH_2_NODE_ID.remove(store, (key.1, node_id));
+    } else if let Some(mut parent_stats) = NODE_STATS.may_load(store, &parent_id)? {
+        parent_stats.n_replies = sub_u32(parent_stats.n_replies, 1)?;
+        NODE_STATS.save(store, &parent_id, &parent_stats)?;
+    }
+
+    for tag in tags.iter() {
+        IX_TAG_2_NODE_ID.remove(store, (&tag.to_lowercase(), n_likes, node_id));
+    }
+
+    // Unpin the node and drop the pins of its own children
+    if let Some(mut pinned_ids) = PINS.may_load(store, &parent_id)? {
+        if pinned_ids.contains(node_id) {
+            pinned_ids.retain(|id| id != node_id);
+            PINS.save(store, &parent_id, &pinned_ids)?;
+        }
+    }
+    PINS.remove(store, node_id);
+
+    // Unaccept the node as its parent's answer and drop its own
+    if ACCEPTED_ANSWERS.may_load(store, &parent_id)?.as_ref() == Some(node_id) {
+        ACCEPTED_ANSWERS.remove(store, &parent_id);
+    }
+    ACCEPTED_ANSWERS.remove(store, node_id);
+
+    let node_num = node_id
+        .parse::<u64>()
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    if let Some(lang) = lang {
+        IX_LANG_2_NODE_ID.remove(store, (&to_primary_language(&lang), node_num));
+    }
+
+    if let Some(mentions) = NODE_MENTIONS.may_load(store, node_id)? {
+        for addr in mentions.iter() {
+            IX_ADDR_2_MENTION_ID.remove(store, (addr, node_num));
+        }
+        NODE_MENTIONS.remove(store, node_id);
+    }
+
+    NODE_HEADER.remove(store, node_id);
+    NODE_STATS.remove(store, node_id);
+    NODE_ATTRS.remove(store, node_id);
+    NODE_CONTENT.remove(store, node_id);
+    NODE_NUM_LIKES.remove(store, node_id);
+
+    // Tick down total count of nodes in tree
+    COUNTERS.update(store, NUM_NODES_COUNTER_KEY, |n| -> Result<_, ContractError> {
+        sub_u64(n.unwrap_or_default(), 1u64)
+    })?;
+
+    Ok(true)
+}
+
+/// Validated, deduplicated addresses mentioned by the node
+fn to_mentioned_addrs(
+    api: &dyn Api,
+    args: &NodeInitArgs,
+) -> Result<Vec<Addr>, ContractError> {
+    let mut addrs = args
+        .mentions
+        .as_ref()
+        .unwrap_or(&vec![])
+        .iter()
+        .map(|address| api.addr_validate(address))
+        .collect::<StdResult<Vec<Addr>>>()?;
+    addrs.sort();
+    addrs.dedup();
+    Ok(addrs)
+}
+
 pub fn increment_counter<T: Into<Uint64>>(
     store: &mut dyn Storage,
     key: &str,
@@ -77,6 +77,30 @@ impl Token {
         })
     }
 
+    /// Send a cw20 token amount from the owner, who must have granted the
+    /// sender of the message an allowance
+    pub fn transfer_from(
+        &self,
+        owner: &Addr,
+        recipient: &Addr,
+        amount: Uint128,
+    ) -> StdResult<SubMsg> {
+        match self {
+            Self::Denom(_) => Err(StdError::GenericErr {
+                msg: "native tokens can't be transferred from another account".to_owned(),
+            }),
+            Self::Address(cw20_addr) => Ok(SubMsg::new(WasmMsg::Execute {
+                contract_addr: cw20_addr.clone().into(),
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: owner.clone().into(),
+                    recipient: recipient.clone().into(),
+                    amount,
+                })?,
+                funds: vec![],
+            })),
+        }
+    }
+
     pub fn burn(
         &self,
         amount: Uint128,
@@ -103,13 +127,10 @@ impl Token {
 
     pub fn find_in_funds(
         &self,
-        funds_to_search: &Vec<Coin>,
+        funds_to_search: &[Coin],
     ) -> Option<Coin> {
         if let Self::Denom(denom) = self {
-            funds_to_search
-                .iter()
-                .find(|c| c.denom == *denom)
-                .and_then(|c| Some(c.clone()))
+            funds_to_search.iter().find(|c| c.denom == *denom).cloned()
         } else {
             None
         }
@@ -117,14 +138,13 @@ impl Token {
 
     pub fn has_in_funds(
         &self,
-        funds_to_search: &Vec<Coin>,
+        funds_to_search: &[Coin],
         exact_amount: Option<Uint128>,
     ) -> bool {
         if let Self::Denom(denom) = self {
             funds_to_search
                 .iter()
-                .find(|c| c.denom == *denom && (exact_amount.and_then(|n| Some(n == c.amount)).unwrap_or(true)))
-                .is_some()
+                .any(|c| c.denom == *denom && exact_amount.map(|n| n == c.amount).unwrap_or(true))
         } else {
             false
         }
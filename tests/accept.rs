@@ -0,0 +1,119 @@
+mod common;
+
+use common::{default_config, exec, load_node, node_init_args, setup, MockDeps};
+use cosmwasm_std::{from_json, Env, Uint128};
+use cw_post::{
+    contract::query,
+    cursor::Cursor,
+    error::ContractError,
+    models::NodeKind,
+    msg::{
+        AcceptAnswerMsg, ExecuteMsg, LikeMsg, NodesByParentIdQueryArgs, NodesPaginationResponse, NodesQueryMsg,
+        OrderBy, QueryMsg,
+    },
+};
+
+fn accept(answer_id: Option<&str>) -> ExecuteMsg {
+    ExecuteMsg::AcceptAnswer(AcceptAnswerMsg {
+        question_id: "2".to_owned(),
+        answer_id: answer_id.map(|id| id.to_owned()),
+    })
+}
+
+/// Query a page of the question's answers, accepted first, returning their IDs
+/// & the next cursor
+fn answers(
+    deps: &MockDeps,
+    env: &Env,
+    cursor: Option<Cursor>,
+) -> (Vec<String>, Option<Cursor>) {
+    let resp: NodesPaginationResponse = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Nodes(NodesQueryMsg::ByParentId(NodesByParentIdQueryArgs {
+                parent_id: "2".to_owned(),
+                order_by: OrderBy::AcceptedFirst,
+                limit: 1,
+                desc: true,
+                cursor,
+                include_pinned_first: None,
+                viewer: None,
+                fields: None,
+                include_labels: None,
+                exclude_labels: None,
+                lang: None,
+            })),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    (resp.nodes.into_iter().map(|node| node.id).collect(), resp.cursor)
+}
+
+#[test]
+fn question_authors_accept_direct_replies_listed_first() {
+    let (mut deps, env) = setup(default_config());
+
+    let mut question = node_init_args("1", "Question");
+    question.kind = Some(NodeKind::Question);
+    exec(&mut deps, &env, "asker", ExecuteMsg::Reply(question)).unwrap();
+    for (answerer, parent_id) in [
+        ("answerer1", "2"),
+        ("answerer2", "2"),
+        ("answerer3", "2"),
+        ("asker", "3"),
+    ] {
+        exec(
+            &mut deps,
+            &env,
+            answerer,
+            ExecuteMsg::Reply(node_init_args(parent_id, "Answer")),
+        )
+        .unwrap();
+    }
+
+    // Rank answer 4 above 5, & 3 last
+    for (liker, node_id) in [("liker1", "4"), ("liker2", "4"), ("liker1", "5")] {
+        let like = ExecuteMsg::Like(LikeMsg {
+            node_id: node_id.to_owned(),
+            tip_amount: Uint128::zero(),
+        });
+        exec(&mut deps, &env, liker, like).unwrap();
+    }
+
+    assert!(matches!(
+        exec(&mut deps, &env, "answerer1", accept(Some("3"))),
+        Err(ContractError::NotAuthorized { .. })
+    ));
+    assert!(matches!(
+        exec(&mut deps, &env, "asker", accept(Some("6"))),
+        Err(ContractError::ValidationError { .. })
+    ));
+    assert_eq!(load_node(&deps, &env, "2").unwrap().accepted_answer_id, None);
+
+    // The accepted answer heads only the first page & is omitted after
+    exec(&mut deps, &env, "asker", accept(Some("3"))).unwrap();
+    assert_eq!(
+        load_node(&deps, &env, "2").unwrap().accepted_answer_id,
+        Some("3".to_owned())
+    );
+    let (page, cursor) = answers(&deps, &env, None);
+    assert_eq!(page, vec!["3", "4"]);
+    let (page, cursor) = answers(&deps, &env, cursor);
+    assert_eq!(page, vec!["5"]);
+    assert_eq!(answers(&deps, &env, cursor).0, Vec::<String>::new());
+
+    exec(&mut deps, &env, "asker", accept(Some("5"))).unwrap();
+    assert_eq!(
+        load_node(&deps, &env, "2").unwrap().accepted_answer_id,
+        Some("5".to_owned())
+    );
+    let (page, cursor) = answers(&deps, &env, None);
+    assert_eq!(page, vec!["5", "4"]);
+    assert_eq!(answers(&deps, &env, cursor).0, vec!["3"]);
+
+    exec(&mut deps, &env, "asker", accept(None)).unwrap();
+    assert_eq!(load_node(&deps, &env, "2").unwrap().accepted_answer_id, None);
+    assert_eq!(answers(&deps, &env, None).0, vec!["4"]);
+}
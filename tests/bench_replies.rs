@@ -0,0 +1,190 @@
+//! Checks that the storage access performed by paginated node queries stays
+//! flat as a thread grows. cw-multi-test does not meter gas, so reads and
+//! bytes read from storage are counted instead, these being what dominate
+//! query gas.
+//!
+//! Run with `cargo test --test bench_replies -- --nocapture` to print counts.
+mod common;
+
+use std::cell::Cell;
+
+use common::{default_config, node_init_args, setup};
+use cosmwasm_std::{testing::mock_info, Deps, Order, QuerierWrapper, Record, Storage};
+use cw_post::{
+    contract::{execute, query},
+    msg::{ChatQueryArgs, ExecuteMsg, NodesByParentIdQueryArgs, NodesQueryMsg, OrderBy, QueryMsg},
+};
+
+const THREAD_SIZES: [usize; 2] = [100, 10_000];
+
+/// Max difference in reads per query between the smallest & largest thread
+const MAX_READ_GROWTH: u64 = 5;
+const PAGE_SIZE: u8 = 50;
+
+/// Read-only Storage wrapper that counts each read & iterated record
+struct CountingStorage<'a> {
+    inner: &'a dyn Storage,
+    n_reads: Cell<u64>,
+    n_bytes: Cell<u64>,
+}
+
+impl<'a> CountingStorage<'a> {
+    fn new(inner: &'a dyn Storage) -> Self {
+        Self {
+            inner,
+            n_reads: Cell::new(0),
+            n_bytes: Cell::new(0),
+        }
+    }
+
+    fn count(
+        &self,
+        n_bytes: usize,
+    ) {
+        self.n_reads.set(self.n_reads.get() + 1);
+        self.n_bytes.set(self.n_bytes.get() + n_bytes as u64);
+    }
+}
+
+impl<'a> Storage for CountingStorage<'a> {
+    fn get(
+        &self,
+        key: &[u8],
+    ) -> Option<Vec<u8>> {
+        let value = self.inner.get(key);
+        self.count(key.len() + value.as_ref().map(|v| v.len()).unwrap_or_default());
+        value
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        Box::new(
+            self.inner
+                .range(start, end, order)
+                .inspect(|(k, v)| self.count(k.len() + v.len())),
+        )
+    }
+
+    fn set(
+        &mut self,
+        _key: &[u8],
+        _value: &[u8],
+    ) {
+        unreachable!("read-only storage")
+    }
+
+    fn remove(
+        &mut self,
+        _key: &[u8],
+    ) {
+        unreachable!("read-only storage")
+    }
+}
+
+#[test]
+fn query_reads_stay_flat_as_thread_grows() {
+    let (mut deps, env) = setup(default_config());
+    let info = mock_info("author", &[]);
+
+    let queries: Vec<(&str, QueryMsg)> = vec![
+        (
+            "by_parent_id/time",
+            QueryMsg::Nodes(NodesQueryMsg::ByParentId(NodesByParentIdQueryArgs {
+                parent_id: "1".to_owned(),
+                order_by: OrderBy::Time,
+                limit: PAGE_SIZE,
+                desc: true,
+                cursor: None,
+                include_pinned_first: None,
+                viewer: None,
+                fields: None,
+                include_labels: None,
+                exclude_labels: None,
+                lang: None,
+            })),
+        ),
+        (
+            "by_parent_id/likes",
+            QueryMsg::Nodes(NodesQueryMsg::ByParentId(NodesByParentIdQueryArgs {
+                parent_id: "1".to_owned(),
+                order_by: OrderBy::Likes,
+                limit: PAGE_SIZE,
+                desc: true,
+                cursor: None,
+                include_pinned_first: None,
+                viewer: None,
+                fields: None,
+                include_labels: None,
+                exclude_labels: None,
+                lang: None,
+            })),
+        ),
+        (
+            "chat",
+            QueryMsg::Chat(ChatQueryArgs {
+                limit: PAGE_SIZE,
+                desc: Some(true),
+                cursor: None,
+                viewer: None,
+                fields: None,
+                include_labels: None,
+                exclude_labels: None,
+                lang: None,
+            }),
+        ),
+    ];
+
+    println!("{:<20} {:>8} {:>8} {:>10}", "query", "replies", "reads", "bytes");
+
+    let mut n_replies = 0;
+    let mut first_reads: Vec<u64> = vec![];
+
+    for thread_size in THREAD_SIZES {
+        while n_replies < thread_size {
+            n_replies += 1;
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                ExecuteMsg::Reply(node_init_args("1", &format!("Reply {}", n_replies))),
+            )
+            .unwrap();
+        }
+
+        for (i, (name, msg)) in queries.iter().enumerate() {
+            let storage = CountingStorage::new(&deps.storage);
+            let counted_deps = Deps {
+                storage: &storage,
+                api: &deps.api,
+                querier: QuerierWrapper::new(&deps.querier),
+            };
+
+            query(counted_deps, env.clone(), msg.clone()).unwrap();
+
+            println!(
+                "{:<20} {:>8} {:>8} {:>10}",
+                name,
+                n_replies,
+                storage.n_reads.get(),
+                storage.n_bytes.get()
+            );
+
+            // Compare each query's reads against those in the smallest thread
+            match first_reads.get(i) {
+                None => first_reads.push(storage.n_reads.get()),
+                Some(n_first) => assert!(
+                    storage.n_reads.get().abs_diff(*n_first) <= MAX_READ_GROWTH,
+                    "{} reads grew from {} to {} at {} replies",
+                    name,
+                    n_first,
+                    storage.n_reads.get(),
+                    n_replies
+                ),
+            }
+        }
+    }
+}
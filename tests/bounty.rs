@@ -0,0 +1,287 @@
+mod common;
+
+use common::{default_config, exec, exec_with_funds, node_init_args, setup, MockDeps, DENOM};
+use cosmwasm_std::{coins, from_json, BankMsg, CosmosMsg, Env, Response, Uint128};
+use cw_post::{
+    contract::query,
+    models::BountyFallback,
+    msg::{
+        AwardBountyMsg, BountyAward, BountyQueryArgs, BountyResponse, ClaimableQueryArgs, ClaimableResponse,
+        ExecuteMsg, FundBountyMsg, LikeMsg, QueryMsg, SettleBountyMsg, TipMsg, UserStatsQueryArgs, UserStatsResponse,
+    },
+    tokens::Token,
+};
+
+const DEADLINE_SECS: u64 = 60;
+
+/// Recipients & amounts of the bank sends in a response
+fn sends(resp: &Response) -> Vec<(String, u128)> {
+    resp.messages
+        .iter()
+        .filter_map(|sub| match &sub.msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                Some((to_address.to_owned(), amount[0].amount.u128()))
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+fn claimable(
+    deps: &MockDeps,
+    env: &Env,
+    address: &str,
+) -> Vec<u128> {
+    let msg = QueryMsg::Claimable(ClaimableQueryArgs {
+        address: address.to_owned(),
+    });
+    let resp: ClaimableResponse = from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    resp.balances.iter().map(|balance| balance.amount.u128()).collect()
+}
+
+fn fund(
+    deps: &mut MockDeps,
+    env: &Env,
+    funder: &str,
+    amount: u128,
+    deadline: bool,
+) {
+    let msg = ExecuteMsg::FundBounty(FundBountyMsg {
+        node_id: "1".to_owned(),
+        amount: Uint128::from(amount),
+        deadline: deadline.then(|| env.block.time.plus_seconds(DEADLINE_SECS)),
+    });
+    exec_with_funds(deps, env, funder, &coins(amount, DENOM), msg).unwrap();
+}
+
+fn like(
+    deps: &mut MockDeps,
+    env: &Env,
+    node_id: &str,
+    n_likes: usize,
+) {
+    for i in 0..n_likes {
+        let msg = ExecuteMsg::Like(LikeMsg {
+            node_id: node_id.to_owned(),
+            tip_amount: Uint128::zero(),
+        });
+        exec(deps, env, &format!("liker{}", i), msg).unwrap();
+    }
+}
+
+#[test]
+fn tips_must_be_funded() {
+    let (mut deps, env) = setup(default_config());
+    let tip = ExecuteMsg::Tip(TipMsg {
+        node_id: "1".to_owned(),
+        tip_amount: Uint128::from(10u128),
+    });
+    let like = ExecuteMsg::Like(LikeMsg {
+        node_id: "1".to_owned(),
+        tip_amount: Uint128::from(10u128),
+    });
+
+    assert!(exec(&mut deps, &env, "tipper", tip.clone()).is_err());
+    assert!(exec_with_funds(&mut deps, &env, "tipper", &coins(5, DENOM), tip.clone()).is_err());
+    assert!(exec(&mut deps, &env, "tipper", like.clone()).is_err());
+
+    // Likes without tips send nothing
+    let free_like = ExecuteMsg::Like(LikeMsg {
+        node_id: "1".to_owned(),
+        tip_amount: Uint128::zero(),
+    });
+    let resp = exec(&mut deps, &env, "tipper", free_like.clone()).unwrap();
+    assert!(resp.messages.is_empty());
+    exec(&mut deps, &env, "tipper", free_like).unwrap();
+
+    let resp = exec_with_funds(&mut deps, &env, "tipper", &coins(10, DENOM), tip).unwrap();
+    assert_eq!(sends(&resp), vec![("author".to_owned(), 10)]);
+    let resp = exec_with_funds(&mut deps, &env, "tipper", &coins(10, DENOM), like).unwrap();
+    assert_eq!(sends(&resp), vec![("author".to_owned(), 10)]);
+}
+
+#[test]
+fn settling_refunds_unawarded_bounty_to_funders_pro_rata() {
+    let (mut deps, mut env) = setup(default_config());
+    fund(&mut deps, &env, "funder1", 60, true);
+    fund(&mut deps, &env, "funder2", 40, false);
+
+    exec(
+        &mut deps,
+        &env,
+        "replier",
+        ExecuteMsg::Reply(node_init_args("1", "Answer")),
+    )
+    .unwrap();
+    let award = ExecuteMsg::AwardBounty(AwardBountyMsg {
+        node_id: "1".to_owned(),
+        awards: vec![BountyAward {
+            node_id: "2".to_owned(),
+            amount: Uint128::from(50u128),
+        }],
+    });
+    let resp = exec(&mut deps, &env, "author", award).unwrap();
+    assert_eq!(sends(&resp), vec![("replier".to_owned(), 50)]);
+
+    let settle = ExecuteMsg::SettleBounty(SettleBountyMsg {
+        node_id: "1".to_owned(),
+    });
+    assert!(exec(&mut deps, &env, "anyone", settle.clone()).is_err());
+
+    env.block.time = env.block.time.plus_seconds(DEADLINE_SECS);
+    let resp = exec(&mut deps, &env, "anyone", settle.clone()).unwrap();
+    assert_eq!(
+        sends(&resp),
+        vec![("funder1".to_owned(), 30), ("funder2".to_owned(), 20)]
+    );
+    assert!(exec(&mut deps, &env, "anyone", settle).is_err());
+}
+
+#[test]
+fn settling_splits_unawarded_bounty_by_likes() {
+    let mut config = default_config();
+    config.bounty_fallback = Some(BountyFallback::SplitByLikes);
+    let (mut deps, mut env) = setup(config);
+    fund(&mut deps, &env, "funder", 100, true);
+
+    for replier in ["replier1", "replier2", "author", "replier3"] {
+        exec(
+            &mut deps,
+            &env,
+            replier,
+            ExecuteMsg::Reply(node_init_args("1", "Answer")),
+        )
+        .unwrap();
+    }
+    like(&mut deps, &env, "2", 3);
+    like(&mut deps, &env, "3", 1);
+    like(&mut deps, &env, "4", 5);
+
+    // The author's own & unliked replies get no share
+    env.block.time = env.block.time.plus_seconds(DEADLINE_SECS);
+    let settle = ExecuteMsg::SettleBounty(SettleBountyMsg {
+        node_id: "1".to_owned(),
+    });
+    let resp = exec(&mut deps, &env, "anyone", settle).unwrap();
+    assert_eq!(
+        sends(&resp),
+        vec![("replier1".to_owned(), 75), ("replier2".to_owned(), 25)]
+    );
+}
+
+#[test]
+fn claimable_balances_only_credit_funded_payouts() {
+    let mut config = default_config();
+    config.claimable_tips = Some(true);
+    let (mut deps, mut env) = setup(config);
+    let tip = ExecuteMsg::Tip(TipMsg {
+        node_id: "1".to_owned(),
+        tip_amount: Uint128::from(10u128),
+    });
+
+    assert!(exec(&mut deps, &env, "tipper", tip.clone()).is_err());
+    assert!(claimable(&deps, &env, "author").is_empty());
+
+    let resp = exec_with_funds(&mut deps, &env, "tipper", &coins(10, DENOM), tip).unwrap();
+    assert!(sends(&resp).is_empty());
+    assert_eq!(claimable(&deps, &env, "author"), vec![10]);
+
+    // Refunds of settled bounties are likewise credited
+    fund(&mut deps, &env, "funder", 100, true);
+    env.block.time = env.block.time.plus_seconds(DEADLINE_SECS);
+    let settle = ExecuteMsg::SettleBounty(SettleBountyMsg {
+        node_id: "1".to_owned(),
+    });
+    let resp = exec(&mut deps, &env, "anyone", settle).unwrap();
+    assert!(sends(&resp).is_empty());
+    assert_eq!(claimable(&deps, &env, "funder"), vec![100]);
+}
+
+#[test]
+fn bounty_payouts_are_not_counted_as_tips() {
+    let (mut deps, env) = setup(default_config());
+    fund(&mut deps, &env, "funder", 100, true);
+    exec(
+        &mut deps,
+        &env,
+        "replier",
+        ExecuteMsg::Reply(node_init_args("1", "Answer")),
+    )
+    .unwrap();
+
+    let award = ExecuteMsg::AwardBounty(AwardBountyMsg {
+        node_id: "1".to_owned(),
+        awards: vec![BountyAward {
+            node_id: "2".to_owned(),
+            amount: Uint128::from(100u128),
+        }],
+    });
+    exec(&mut deps, &env, "author", award).unwrap();
+
+    for address in ["replier", "funder"] {
+        let msg = QueryMsg::UserStats(UserStatsQueryArgs {
+            address: address.to_owned(),
+        });
+        let stats: UserStatsResponse = from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert!(stats.received.is_empty() && stats.given.is_empty());
+    }
+}
+
+#[test]
+fn bounties_are_funded_and_paid_in_the_token_opened_in() {
+    let mut config = default_config();
+    let (mut deps, mut env) = setup(config.clone());
+    fund(&mut deps, &env, "funder1", 60, true);
+
+    config.token = Token::Denom("uother".to_owned());
+    exec(&mut deps, &env, "author", ExecuteMsg::Configure(config)).unwrap();
+
+    // Top-ups must be in the bounty's token
+    let top_up = ExecuteMsg::FundBounty(FundBountyMsg {
+        node_id: "1".to_owned(),
+        amount: Uint128::from(40u128),
+        deadline: None,
+    });
+    assert!(exec_with_funds(&mut deps, &env, "funder2", &coins(40, "uother"), top_up).is_err());
+    fund(&mut deps, &env, "funder2", 40, false);
+
+    let msg = QueryMsg::Bounty(BountyQueryArgs {
+        node_id: "1".to_owned(),
+    });
+    let bounty: BountyResponse = from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(bounty.token, Token::Denom(DENOM.to_owned()));
+
+    let denoms = |resp: &Response| -> Vec<String> {
+        resp.messages
+            .iter()
+            .filter_map(|sub| match &sub.msg {
+                CosmosMsg::Bank(BankMsg::Send { amount, .. }) => Some(amount[0].denom.to_owned()),
+                _ => None,
+            })
+            .collect()
+    };
+
+    exec(
+        &mut deps,
+        &env,
+        "replier",
+        ExecuteMsg::Reply(node_init_args("1", "Answer")),
+    )
+    .unwrap();
+    let award = ExecuteMsg::AwardBounty(AwardBountyMsg {
+        node_id: "1".to_owned(),
+        awards: vec![BountyAward {
+            node_id: "2".to_owned(),
+            amount: Uint128::from(50u128),
+        }],
+    });
+    let resp = exec(&mut deps, &env, "author", award).unwrap();
+    assert_eq!(denoms(&resp), vec![DENOM]);
+
+    env.block.time = env.block.time.plus_seconds(DEADLINE_SECS);
+    let settle = ExecuteMsg::SettleBounty(SettleBountyMsg {
+        node_id: "1".to_owned(),
+    });
+    let resp = exec(&mut deps, &env, "anyone", settle).unwrap();
+    assert_eq!(denoms(&resp), vec![DENOM, DENOM]);
+}
@@ -0,0 +1,139 @@
+//! Helpers shared by integration tests
+
+#![allow(dead_code)]
+
+use cosmwasm_std::{
+    from_json,
+    testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+    Coin, Env, OwnedDeps, Response, Uint128,
+};
+use cw_post::{
+    contract::{execute, instantiate, query},
+    error::ContractError,
+    models::{Config, FeeParams, Node},
+    msg::{
+        ExecuteMsg, InstantiateMsg, NodeInitArgs, NodesByIdQueryArgs, NodesPaginationResponse, NodesQueryMsg, OrderBy,
+        QueryMsg,
+    },
+    tokens::Token,
+};
+
+pub type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+pub const DENOM: &str = "ujunox";
+
+pub fn default_config() -> Config {
+    Config {
+        token: Token::Denom(DENOM.to_owned()),
+        fee_recipient: None,
+        fees: FeeParams {
+            creation: Uint128::zero(),
+            reaction: Uint128::zero(),
+            link: Uint128::zero(),
+            text: Uint128::zero(),
+            tag: Uint128::zero(),
+            tip_pct: Uint128::zero(),
+            body_ref: None,
+        },
+        max_depth: None,
+        limits: None,
+        links: None,
+        sanitize_mode: None,
+        node_ttl: None,
+        prune_bounty: None,
+        max_pins: None,
+        bounty_fallback: None,
+        claimable_tips: None,
+    }
+}
+
+pub fn node_init_args(
+    parent_id: &str,
+    title: &str,
+) -> NodeInitArgs {
+    NodeInitArgs {
+        parent_id: parent_id.to_owned(),
+        title: title.to_owned(),
+        body: Some("Lorem ipsum dolor sit amet, consectetur adipiscing elit.".to_owned()),
+        body_ref: None,
+        links: None,
+        tags: None,
+        labels: None,
+        lang: None,
+        translations: None,
+        reply_policy: None,
+        child_reply_policy: None,
+        kind: None,
+        quote: None,
+        mentions: None,
+        poll: None,
+        publish_at: None,
+        expires_at: None,
+    }
+}
+
+/// Instantiate the contract with the given config & a root node by "author"
+pub fn setup(config: Config) -> (MockDeps, Env) {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("author", &[]),
+        InstantiateMsg {
+            config,
+            operator: None,
+            root: node_init_args("", "Root"),
+        },
+    )
+    .unwrap();
+    (deps, env)
+}
+
+pub fn exec(
+    deps: &mut MockDeps,
+    env: &Env,
+    sender: &str,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg)
+}
+
+pub fn exec_with_funds(
+    deps: &mut MockDeps,
+    env: &Env,
+    sender: &str,
+    funds: &[Coin],
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    execute(deps.as_mut(), env.clone(), mock_info(sender, funds), msg)
+}
+
+/// Load a node as seen by a query with no viewer, if visible
+pub fn load_node(
+    deps: &MockDeps,
+    env: &Env,
+    node_id: &str,
+) -> Option<Node> {
+    let resp: NodesPaginationResponse = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Nodes(NodesQueryMsg::ByIds(NodesByIdQueryArgs {
+                ids: vec![node_id.to_owned()],
+                order_by: OrderBy::Time,
+                limit: 1,
+                desc: false,
+                cursor: None,
+                viewer: None,
+                fields: None,
+                include_labels: None,
+                exclude_labels: None,
+                lang: None,
+            })),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    resp.nodes.into_iter().next()
+}
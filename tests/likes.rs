@@ -0,0 +1,44 @@
+mod common;
+
+use common::{default_config, exec, node_init_args, setup};
+use cosmwasm_std::{from_json, Uint128};
+use cw_post::{
+    contract::query,
+    msg::{ExecuteMsg, LikeMsg, NodesByParentIdQueryArgs, NodesPaginationResponse, NodesQueryMsg, OrderBy, QueryMsg},
+};
+
+#[test]
+fn unliked_replies_stay_listed_by_likes() {
+    let (mut deps, env) = setup(default_config());
+    exec(
+        &mut deps,
+        &env,
+        "replier",
+        ExecuteMsg::Reply(node_init_args("1", "Reply")),
+    )
+    .unwrap();
+
+    let like = ExecuteMsg::Like(LikeMsg {
+        node_id: "2".to_owned(),
+        tip_amount: Uint128::zero(),
+    });
+    exec(&mut deps, &env, "liker", like.clone()).unwrap();
+    exec(&mut deps, &env, "liker", like).unwrap();
+
+    let msg = QueryMsg::Nodes(NodesQueryMsg::ByParentId(NodesByParentIdQueryArgs {
+        parent_id: "1".to_owned(),
+        order_by: OrderBy::Likes,
+        limit: 10,
+        desc: true,
+        cursor: None,
+        include_pinned_first: None,
+        viewer: None,
+        fields: None,
+        include_labels: None,
+        exclude_labels: None,
+        lang: None,
+    }));
+    let resp: NodesPaginationResponse = from_json(query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    let ids: Vec<String> = resp.nodes.into_iter().map(|node| node.id).collect();
+    assert_eq!(ids, vec!["2"]);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::{default_config, exec, node_init_args, setup};
+use cosmwasm_std::from_json;
+use cw_post::{
+    contract::query,
+    models::Label,
+    msg::{ExecuteMsg, NodesPaginationResponse, PinMsg, PinnedQueryArgs, QueryMsg},
+};
+
+#[test]
+fn pinned_query_applies_label_and_language_filters() {
+    let (mut deps, env) = setup(default_config());
+
+    let mut spoiler = node_init_args("1", "Spoiler");
+    spoiler.labels = Some(vec![Label::Spoiler]);
+    spoiler.lang = Some("fr".to_owned());
+    exec(&mut deps, &env, "author", ExecuteMsg::Reply(spoiler)).unwrap();
+    let mut plain = node_init_args("1", "Plain");
+    plain.lang = Some("en".to_owned());
+    exec(&mut deps, &env, "author", ExecuteMsg::Reply(plain)).unwrap();
+    for node_id in ["2", "3"] {
+        let pin = ExecuteMsg::Pin(PinMsg {
+            node_id: node_id.to_owned(),
+        });
+        exec(&mut deps, &env, "author", pin).unwrap();
+    }
+
+    let query_pinned = |args: PinnedQueryArgs| -> Vec<String> {
+        let resp: NodesPaginationResponse =
+            from_json(query(deps.as_ref(), env.clone(), QueryMsg::Pinned(args)).unwrap()).unwrap();
+        resp.nodes.into_iter().map(|node| node.id).collect()
+    };
+    let args = PinnedQueryArgs {
+        parent_id: "1".to_owned(),
+        viewer: None,
+        fields: None,
+        include_labels: None,
+        exclude_labels: None,
+        lang: None,
+    };
+
+    assert_eq!(query_pinned(args.clone()), vec!["2", "3"]);
+    assert_eq!(
+        query_pinned(PinnedQueryArgs {
+            exclude_labels: Some(vec![Label::Spoiler]),
+            ..args.clone()
+        }),
+        vec!["3"]
+    );
+    assert_eq!(
+        query_pinned(PinnedQueryArgs {
+            lang: Some("fr".to_owned()),
+            ..args
+        }),
+        vec!["2"]
+    );
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use common::{default_config, exec, exec_with_funds, load_node, node_init_args, setup, DENOM};
+use cosmwasm_std::{coin, coins, BankMsg, CosmosMsg, Response, Uint128};
+use cw_post::{
+    models::Reaction,
+    msg::{ExecuteMsg, FundBountyMsg, LikeMsg, PruneMsg, ReactMsg, TipMsg},
+    tokens::Token,
+};
+
+const TTL: u64 = 60;
+const FEE: u128 = 100;
+
+fn prune(resp: &Response) -> (String, Option<Uint128>) {
+    let n_pruned = resp
+        .attributes
+        .iter()
+        .find(|a| a.key == "n_pruned")
+        .unwrap()
+        .value
+        .to_owned();
+    let paid = resp.messages.iter().find_map(|sub| match &sub.msg {
+        CosmosMsg::Bank(BankMsg::Send { amount, .. }) => Some(amount[0].amount),
+        _ => None,
+    });
+    (n_pruned, paid)
+}
+
+#[test]
+fn prune_bounties_are_paid_from_pooled_creation_fees() {
+    let mut config = default_config();
+    config.node_ttl = Some(TTL);
+    config.prune_bounty = Some(Uint128::from(FEE * 2));
+    config.fees.creation = Uint128::from(FEE);
+    let (mut deps, mut env) = setup(config);
+
+    // Expiring replies must pay the creation fee
+    let reply = ExecuteMsg::Reply(node_init_args("1", "Expiring"));
+    assert!(exec(&mut deps, &env, "replier", reply.clone()).is_err());
+    for _ in 0..2 {
+        exec_with_funds(&mut deps, &env, "replier", &coins(FEE, DENOM), reply.clone()).unwrap();
+    }
+
+    // The bounty is capped by the pool of fees paid
+    env.block.time = env.block.time.plus_seconds(TTL);
+    let msg = ExecuteMsg::Prune(PruneMsg { limit: 1 });
+    let (n_pruned, paid) = prune(&exec(&mut deps, &env, "pruner", msg.clone()).unwrap());
+    assert_eq!((n_pruned.as_str(), paid), ("1", Some(Uint128::from(FEE * 2))));
+    let (n_pruned, paid) = prune(&exec(&mut deps, &env, "pruner", msg).unwrap());
+    assert_eq!((n_pruned.as_str(), paid), ("1", None));
+    assert!(load_node(&deps, &env, "2").is_none());
+}
+
+#[test]
+fn prune_removes_heavily_liked_nodes_over_several_calls() {
+    let mut config = default_config();
+    config.node_ttl = Some(TTL);
+    let (mut deps, mut env) = setup(config);
+
+    exec(
+        &mut deps,
+        &env,
+        "replier",
+        ExecuteMsg::Reply(node_init_args("1", "Popular")),
+    )
+    .unwrap();
+    for i in 0..150 {
+        let like = ExecuteMsg::Like(LikeMsg {
+            node_id: "2".to_owned(),
+            tip_amount: Uint128::zero(),
+        });
+        exec(&mut deps, &env, &format!("liker{}", i), like).unwrap();
+    }
+
+    env.block.time = env.block.time.plus_seconds(TTL);
+    let msg = ExecuteMsg::Prune(PruneMsg { limit: 10 });
+    let (n_pruned, _) = prune(&exec(&mut deps, &env, "pruner", msg.clone()).unwrap());
+    assert_eq!(n_pruned, "0");
+    let (n_pruned, _) = prune(&exec(&mut deps, &env, "pruner", msg).unwrap());
+    assert_eq!(n_pruned, "1");
+    assert_eq!(load_node(&deps, &env, "1").unwrap().n_replies, 0);
+}
+
+#[test]
+fn prune_bounties_are_paid_in_the_token_of_the_pooled_fees() {
+    let mut config = default_config();
+    config.node_ttl = Some(TTL);
+    config.prune_bounty = Some(Uint128::from(FEE));
+    config.fees.creation = Uint128::from(FEE);
+    let (mut deps, mut env) = setup(config.clone());
+
+    let reply = ExecuteMsg::Reply(node_init_args("1", "Expiring"));
+    exec_with_funds(&mut deps, &env, "replier", &coins(FEE, DENOM), reply.clone()).unwrap();
+
+    // Fees paid after the token changes are pooled separately
+    config.token = Token::Denom("uother".to_owned());
+    exec(&mut deps, &env, "author", ExecuteMsg::Configure(config)).unwrap();
+    exec_with_funds(&mut deps, &env, "replier", &coins(FEE, "uother"), reply).unwrap();
+
+    env.block.time = env.block.time.plus_seconds(TTL);
+    let resp = exec(&mut deps, &env, "pruner", ExecuteMsg::Prune(PruneMsg { limit: 10 })).unwrap();
+    let paid = resp
+        .messages
+        .iter()
+        .filter_map(|sub| match &sub.msg {
+            CosmosMsg::Bank(BankMsg::Send { amount, .. }) => Some(amount[0].to_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(paid, vec![coin(FEE, DENOM), coin(FEE, "uother")]);
+}
+
+#[test]
+fn expired_nodes_cant_be_liked_reacted_to_tipped_or_funded() {
+    let mut config = default_config();
+    config.node_ttl = Some(TTL);
+    let (mut deps, mut env) = setup(config);
+
+    exec(
+        &mut deps,
+        &env,
+        "replier",
+        ExecuteMsg::Reply(node_init_args("1", "Expiring")),
+    )
+    .unwrap();
+
+    let node_id = "2".to_owned();
+    let like = ExecuteMsg::Like(LikeMsg {
+        node_id: node_id.to_owned(),
+        tip_amount: Uint128::zero(),
+    });
+    let react = ExecuteMsg::React(ReactMsg {
+        node_id: node_id.to_owned(),
+        reaction: Reaction::Emoji("👍".to_owned()),
+    });
+    let tip = ExecuteMsg::Tip(TipMsg {
+        node_id: node_id.to_owned(),
+        tip_amount: Uint128::from(FEE),
+    });
+    let fund = ExecuteMsg::FundBounty(FundBountyMsg {
+        node_id,
+        amount: Uint128::from(FEE),
+        deadline: Some(env.block.time.plus_seconds(TTL * 2)),
+    });
+
+    env.block.time = env.block.time.plus_seconds(TTL);
+    assert!(exec(&mut deps, &env, "liker", like).is_err());
+    assert!(exec(&mut deps, &env, "reactor", react).is_err());
+    assert!(exec_with_funds(&mut deps, &env, "tipper", &coins(FEE, DENOM), tip).is_err());
+    assert!(exec_with_funds(&mut deps, &env, "funder", &coins(FEE, DENOM), fund).is_err());
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::{default_config, exec, node_init_args, setup};
+use cosmwasm_std::{Addr, Binary, ContractResult, SystemError, SystemResult, WasmQuery};
+use cw_post::{models::QuoteRef, msg::ExecuteMsg};
+
+const OTHER_CONTRACT: &str = "otherpost";
+
+/// Query sent by quoting replies, in the shape every cw-post version accepts
+fn by_ids_query(node_id: &str) -> String {
+    format!(
+        r#"{{"nodes":{{"by_ids":{{"ids":["{}"],"order_by":"time","limit":1,"desc":false,"cursor":null}}}}}}"#,
+        node_id
+    )
+}
+
+fn quoting_reply(
+    contract: &str,
+    node_id: &str,
+) -> ExecuteMsg {
+    let mut args = node_init_args("1", "Quoting");
+    args.quote = Some(QuoteRef {
+        contract: Addr::unchecked(contract),
+        node_id: node_id.to_owned(),
+    });
+    ExecuteMsg::Reply(args)
+}
+
+#[test]
+fn quotes_only_visible_nodes_in_this_contract() {
+    let (mut deps, mut env) = setup(default_config());
+    let contract = env.contract.address.to_string();
+    let publish_at = env.block.time.plus_seconds(60);
+    let expires_at = env.block.time.plus_seconds(120);
+
+    let mut args = node_init_args("1", "Scheduled");
+    args.publish_at = Some(publish_at);
+    exec(&mut deps, &env, "author", ExecuteMsg::Reply(args)).unwrap();
+
+    let mut args = node_init_args("1", "Expiring");
+    args.expires_at = Some(expires_at);
+    exec(&mut deps, &env, "author", ExecuteMsg::Reply(args)).unwrap();
+
+    assert!(exec(&mut deps, &env, "quoter", quoting_reply(&contract, "404")).is_err());
+
+    // Scheduled nodes can't be quoted, even by their author, until published
+    assert!(exec(&mut deps, &env, "quoter", quoting_reply(&contract, "2")).is_err());
+    assert!(exec(&mut deps, &env, "author", quoting_reply(&contract, "2")).is_err());
+    exec(&mut deps, &env, "quoter", quoting_reply(&contract, "3")).unwrap();
+
+    env.block.time = publish_at;
+    exec(&mut deps, &env, "quoter", quoting_reply(&contract, "2")).unwrap();
+
+    // Expired nodes can't be quoted, though not yet pruned
+    env.block.time = expires_at;
+    assert!(exec(&mut deps, &env, "quoter", quoting_reply(&contract, "3")).is_err());
+}
+
+#[test]
+fn quotes_nodes_in_other_contracts_of_any_version() {
+    let (mut deps, env) = setup(default_config());
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == OTHER_CONTRACT => {
+            // Answer with an older node shape & only for the minimal query
+            let msg = std::str::from_utf8(msg.as_slice()).unwrap();
+            let nodes = if msg == by_ids_query("7") {
+                r#"[{"id":"7","status":"normal","parent_id":"1","n_replies":0,"royalties":"0","title":"Old"}]"#
+            } else if msg == by_ids_query("8") {
+                r#"[{"id":"8","status":"deleted","parent_id":"1","n_replies":0,"royalties":"0","title":""}]"#
+            } else if msg == by_ids_query("9") {
+                "[]"
+            } else {
+                return SystemResult::Ok(ContractResult::Err("unknown field".to_owned()));
+            };
+            let response = format!(r#"{{"cursor":null,"nodes":{}}}"#, nodes);
+            SystemResult::Ok(ContractResult::Ok(Binary::from(response.into_bytes())))
+        },
+        _ => SystemResult::Err(SystemError::Unknown {}),
+    });
+
+    exec(&mut deps, &env, "quoter", quoting_reply(OTHER_CONTRACT, "7")).unwrap();
+    assert!(exec(&mut deps, &env, "quoter", quoting_reply(OTHER_CONTRACT, "8")).is_err());
+    assert!(exec(&mut deps, &env, "quoter", quoting_reply(OTHER_CONTRACT, "9")).is_err());
+    assert!(exec(&mut deps, &env, "quoter", quoting_reply("missingpost", "7")).is_err());
+}
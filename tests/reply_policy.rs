@@ -0,0 +1,41 @@
+mod common;
+
+use common::{default_config, exec, node_init_args, setup};
+use cw_post::{
+    error::ContractError,
+    models::ReplyPolicy,
+    msg::{ExecuteMsg, SetReplyPolicyMsg},
+};
+
+#[test]
+fn child_reply_policy_lets_an_ama_host_answer_questions_alone() {
+    let (mut deps, env) = setup(default_config());
+
+    // The host opens an AMA whose questions only the host may answer
+    let mut ama = node_init_args("1", "AMA");
+    ama.child_reply_policy = Some(ReplyPolicy::AuthorOnly);
+    exec(&mut deps, &env, "host", ExecuteMsg::Reply(ama)).unwrap();
+
+    // Anyone may ask, but even a question's own policy doesn't let others answer
+    let mut question = node_init_args("2", "Question");
+    question.reply_policy = Some(ReplyPolicy::Anyone);
+    exec(&mut deps, &env, "asker", ExecuteMsg::Reply(question)).unwrap();
+
+    let answer = ExecuteMsg::Reply(node_init_args("3", "Answer"));
+    for sender in ["asker", "other"] {
+        assert!(matches!(
+            exec(&mut deps, &env, sender, answer.clone()),
+            Err(ContractError::NotAuthorized { .. })
+        ));
+    }
+    exec(&mut deps, &env, "host", answer.clone()).unwrap();
+
+    // Removing the child policy reopens the questions
+    let msg = ExecuteMsg::SetReplyPolicy(SetReplyPolicyMsg {
+        node_id: "2".to_owned(),
+        reply_policy: ReplyPolicy::Anyone,
+        child_reply_policy: None,
+    });
+    exec(&mut deps, &env, "host", msg).unwrap();
+    exec(&mut deps, &env, "other", answer).unwrap();
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::{default_config, exec, node_init_args, setup};
+use cosmwasm_std::from_json;
+use cw_post::{
+    contract::query,
+    cursor::CursorKind,
+    models::Label,
+    msg::{ChatPaginationResponse, ChatQueryArgs, ExecuteMsg, QueryMsg},
+    query::nodes::MAX_SCANNED_KEYS,
+};
+
+#[test]
+fn filtered_pages_stop_at_the_scan_limit_with_a_cursor() {
+    let (mut deps, env) = setup(default_config());
+    let n_nodes = MAX_SCANNED_KEYS + 10;
+    for i in 1..n_nodes {
+        exec(
+            &mut deps,
+            &env,
+            "author",
+            ExecuteMsg::Reply(node_init_args("1", &format!("Reply {}", i))),
+        )
+        .unwrap();
+    }
+
+    let query_chat = |cursor| -> ChatPaginationResponse {
+        let msg = QueryMsg::Chat(ChatQueryArgs {
+            limit: 10,
+            desc: None,
+            cursor,
+            viewer: None,
+            fields: None,
+            include_labels: Some(vec![Label::Nsfw]),
+            exclude_labels: None,
+            lang: None,
+        });
+        from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap()
+    };
+
+    // No node matches, so the first page ends empty at the last scanned ID
+    let page = query_chat(None);
+    assert!(page.nodes.is_empty());
+    let cursor = page.cursor.unwrap();
+    assert_eq!(
+        cursor.decode_id(CursorKind::Chat).unwrap(),
+        MAX_SCANNED_KEYS.to_string()
+    );
+
+    // The rest are scanned by the next page, which ends the listing
+    let page = query_chat(Some(cursor));
+    assert!(page.nodes.is_empty());
+    assert!(page.cursor.is_none());
+}
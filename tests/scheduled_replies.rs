@@ -0,0 +1,145 @@
+mod common;
+
+use common::{default_config, exec, exec_with_funds, load_node, node_init_args, setup, DENOM};
+use cosmwasm_std::{coins, Uint128};
+use cw_post::{
+    msg::{AwardBountyMsg, BountyAward, ExecuteMsg, FundBountyMsg, LikeMsg, PruneMsg, TipMsg},
+    query::nodes::MAX_COUNTED_SCHEDULED_REPLIES,
+};
+
+#[test]
+fn published_scheduled_replies_are_added_to_reply_counts() {
+    let (mut deps, mut env) = setup(default_config());
+    let publish_at = env.block.time.plus_seconds(60);
+
+    let mut args = node_init_args("1", "Scheduled");
+    args.publish_at = Some(publish_at);
+    exec(&mut deps, &env, "replier", ExecuteMsg::Reply(args)).unwrap();
+
+    // Hidden & uncounted until published
+    assert_eq!(load_node(&deps, &env, "1").unwrap().n_replies, 0);
+    assert!(load_node(&deps, &env, "2").is_none());
+
+    // Can't be liked before publication
+    let like = ExecuteMsg::Like(LikeMsg {
+        node_id: "2".to_owned(),
+        tip_amount: Uint128::zero(),
+    });
+    assert!(exec(&mut deps, &env, "liker", like.clone()).is_err());
+
+    // Counted by queries once published
+    env.block.time = publish_at.plus_seconds(1);
+    assert_eq!(load_node(&deps, &env, "1").unwrap().n_replies, 1);
+    assert!(load_node(&deps, &env, "2").is_some());
+
+    // Added to the stored count by Prune, & counted once
+    let resp = exec(&mut deps, &env, "pruner", ExecuteMsg::Prune(PruneMsg { limit: 10 })).unwrap();
+    assert!(resp
+        .attributes
+        .iter()
+        .any(|a| a.key == "published_node_id" && a.value == "2"));
+    assert_eq!(load_node(&deps, &env, "1").unwrap().n_replies, 1);
+
+    exec(
+        &mut deps,
+        &env,
+        "replier",
+        ExecuteMsg::Reply(node_init_args("1", "Now")),
+    )
+    .unwrap();
+    assert_eq!(load_node(&deps, &env, "1").unwrap().n_replies, 2);
+    exec(&mut deps, &env, "liker", like).unwrap();
+}
+
+#[test]
+fn replies_add_published_scheduled_replies_to_reply_counts() {
+    let (mut deps, mut env) = setup(default_config());
+    let publish_at = env.block.time.plus_seconds(60);
+
+    let mut args = node_init_args("1", "Scheduled");
+    args.publish_at = Some(publish_at);
+    args.mentions = Some(vec!["friend".to_owned()]);
+    let resp = exec(&mut deps, &env, "replier", ExecuteMsg::Reply(args)).unwrap();
+
+    // Mentions aren't announced before publication
+    assert!(!resp.attributes.iter().any(|a| a.key == "mention"));
+
+    env.block.time = publish_at;
+    let resp = exec(
+        &mut deps,
+        &env,
+        "replier",
+        ExecuteMsg::Reply(node_init_args("1", "Now")),
+    )
+    .unwrap();
+    assert!(resp
+        .attributes
+        .iter()
+        .any(|a| a.key == "mention" && a.value == "friend"));
+    assert_eq!(load_node(&deps, &env, "1").unwrap().n_replies, 2);
+}
+
+#[test]
+fn unpublished_replies_cant_be_tipped_funded_or_awarded() {
+    let (mut deps, mut env) = setup(default_config());
+    let publish_at = env.block.time.plus_seconds(60);
+
+    let mut args = node_init_args("1", "Scheduled");
+    args.publish_at = Some(publish_at);
+    exec(&mut deps, &env, "replier", ExecuteMsg::Reply(args)).unwrap();
+
+    let tip = ExecuteMsg::Tip(TipMsg {
+        node_id: "2".to_owned(),
+        tip_amount: Uint128::from(10u128),
+    });
+    let fund = |node_id: &str| {
+        ExecuteMsg::FundBounty(FundBountyMsg {
+            node_id: node_id.to_owned(),
+            amount: Uint128::from(10u128),
+            deadline: Some(publish_at.plus_seconds(60)),
+        })
+    };
+    let award = ExecuteMsg::AwardBounty(AwardBountyMsg {
+        node_id: "1".to_owned(),
+        awards: vec![BountyAward {
+            node_id: "2".to_owned(),
+            amount: Uint128::from(10u128),
+        }],
+    });
+
+    assert!(exec_with_funds(&mut deps, &env, "tipper", &coins(10, DENOM), tip.clone()).is_err());
+    assert!(exec_with_funds(&mut deps, &env, "funder", &coins(10, DENOM), fund("2")).is_err());
+    exec_with_funds(&mut deps, &env, "author", &coins(10, DENOM), fund("1")).unwrap();
+    assert!(exec(&mut deps, &env, "author", award.clone()).is_err());
+
+    env.block.time = publish_at;
+    exec_with_funds(&mut deps, &env, "tipper", &coins(10, DENOM), tip).unwrap();
+    exec_with_funds(&mut deps, &env, "funder", &coins(10, DENOM), fund("2")).unwrap();
+    exec(&mut deps, &env, "author", award).unwrap();
+}
+
+#[test]
+fn caps_published_replies_counted_by_queries() {
+    let (mut deps, mut env) = setup(default_config());
+    let publish_at = env.block.time.plus_seconds(60);
+    let n_scheduled = MAX_COUNTED_SCHEDULED_REPLIES as u32 + 5;
+
+    for _ in 0..n_scheduled {
+        let mut args = node_init_args("1", "Scheduled");
+        args.publish_at = Some(publish_at);
+        exec(&mut deps, &env, "replier", ExecuteMsg::Reply(args)).unwrap();
+    }
+
+    env.block.time = publish_at;
+    assert_eq!(
+        load_node(&deps, &env, "1").unwrap().n_replies,
+        MAX_COUNTED_SCHEDULED_REPLIES as u32
+    );
+
+    // The rest are counted as Prune publishes them
+    while load_node(&deps, &env, "1").unwrap().n_replies < n_scheduled {
+        exec(&mut deps, &env, "pruner", ExecuteMsg::Prune(PruneMsg { limit: 10 })).unwrap();
+    }
+    exec(&mut deps, &env, "pruner", ExecuteMsg::Prune(PruneMsg { limit: 10 })).unwrap();
+    assert_eq!(load_node(&deps, &env, "1").unwrap().n_replies, n_scheduled);
+}